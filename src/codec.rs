@@ -27,6 +27,12 @@ impl<T> WithOffset<T> {
     }
 }
 
+impl<T: std::fmt::Display> std::fmt::Display for WithOffset<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "at offset {}: {}", self.offset, self.value)
+    }
+}
+
 #[derive(Debug, Copy, Clone, Hash, PartialEq)]
 pub struct WithSize<T> {
     pub size: usize,
@@ -51,11 +57,34 @@ impl<T> WithSize<T> {
     }
 }
 
+impl<T: std::fmt::Display> std::fmt::Display for WithSize<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} ({} bytes)", self.value, self.size)
+    }
+}
+
 // TODO Bad name
 #[derive(Debug, Copy, Clone, Hash, PartialEq)]
 pub enum StdError {
     MissingBytes(usize),
 }
+impl StdError {
+    /// Minimum number of bytes missing to decode the item, if the error is caused by a
+    /// truncated input.
+    pub fn missing_bytes(&self) -> Option<usize> {
+        match self {
+            Self::MissingBytes(n) => Some(*n),
+        }
+    }
+}
+
+impl std::fmt::Display for StdError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::MissingBytes(n) => write!(f, "missing {} bytes", n),
+        }
+    }
+}
 
 /// Trait implemented by any item that is encodable to a byte array and decodable from a byte
 /// array.
@@ -75,6 +104,8 @@ pub trait Codec: core::marker::Sized {
 
     /// Attempt to decode a byte array to produce an item.
     /// May return the item with the bytes consumed, a request for more bytes or a parsing error
+    ///
+    /// Must not panic, whatever the content of `out`, and must not consume more than `out`.
     fn decode(out: &[u8]) -> Result<WithSize<Self>, WithOffset<Self::Error>>;
 
     /// Allocate a byte array of the right size and encode the item in it.
@@ -84,3 +115,12 @@ pub trait Codec: core::marker::Sized {
         data
     }
 }
+
+#[test]
+fn test_display() {
+    assert_eq!(
+        WithOffset::new(3, StdError::MissingBytes(2)).to_string(),
+        "at offset 3: missing 2 bytes"
+    );
+    assert_eq!(WithSize::new(4, 0x42).to_string(), "66 (4 bytes)");
+}
@@ -0,0 +1,63 @@
+//! [defmt](https://defmt.ferrous-systems.com) support.
+//!
+//! The formatting reuses the `Display` implementations, so that logs look the same whatever
+//! the logging backend.
+
+macro_rules! impl_defmt_format {
+    ($($ty: ty),* $(,)?) => {
+        $(
+            impl defmt::Format for $ty {
+                fn format(&self, f: defmt::Formatter) {
+                    defmt::write!(f, "{}", defmt::Display2Format(self))
+                }
+            }
+        )*
+    };
+}
+
+mod spec {
+    use crate::spec::v1_2::{action, dash7, operand, Action, Command};
+    impl_defmt_format!(
+        Command,
+        Action,
+        action::OpCode,
+        action::Status,
+        dash7::Address,
+        dash7::InterfaceConfiguration,
+        dash7::InterfaceStatus,
+        operand::ActionStatus,
+        operand::IndirectInterface,
+        operand::InterfaceConfiguration,
+        operand::InterfaceStatus,
+        operand::Permission,
+        operand::Query,
+    );
+}
+
+mod sub_iot {
+    use crate::sub_iot::v0::{dash7, operand, Action, Command};
+    impl_defmt_format!(
+        Command,
+        Action,
+        dash7::InterfaceConfiguration,
+        operand::InterfaceConfiguration,
+    );
+}
+
+mod wizzilab {
+    use crate::wizzilab::v5_3::{action, dash7, operand, Action, Command};
+    impl_defmt_format!(
+        Command,
+        Action,
+        action::OpCode,
+        action::Status,
+        action::TxStatus,
+        dash7::Address,
+        dash7::InterfaceConfiguration,
+        dash7::InterfaceStatus,
+        operand::ActionStatus,
+        operand::IndirectInterface,
+        operand::InterfaceConfiguration,
+        operand::InterfaceStatus,
+    );
+}
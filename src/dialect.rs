@@ -0,0 +1,197 @@
+//! Dialect agnostic entry point, for applications talking to devices using different ALP
+//! flavours.
+//!
+//! The dialect of a command cannot be detected from its content: the dialects share most of
+//! their opcodes and some of them are encoded differently (or mean something else) depending on
+//! the dialect. The dialect of the peer must thus be known and specified by the caller.
+use crate::{
+    codec::WithOffset, spec::v1_2 as spec, sub_iot::v0 as sub_iot, wizzilab::v5_3 as wizzilab,
+};
+
+/// ALP flavours supported by this library
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AlpDialect {
+    /// Specification compliant ALP v1.2 (see [`crate::spec::v1_2`])
+    SpecV1_2,
+    /// Sub-IoT stack ALP (see [`crate::sub_iot::v0`])
+    SubIotV0,
+    /// Wizzilab stack ALP v5.3 (see [`crate::wizzilab::v5_3`])
+    WizzilabV5_3,
+}
+impl std::fmt::Display for AlpDialect {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::SpecV1_2 => write!(f, "spec-v1.2"),
+            Self::SubIotV0 => write!(f, "sub_iot-v0"),
+            Self::WizzilabV5_3 => write!(f, "wizzilab-v5.3"),
+        }
+    }
+}
+
+/// Command of any of the supported dialects
+#[derive(Clone, Debug, PartialEq)]
+pub enum AnyCommand {
+    SpecV1_2(spec::Command),
+    SubIotV0(sub_iot::Command),
+    WizzilabV5_3(wizzilab::Command),
+}
+impl AnyCommand {
+    /// Dialect the command belongs to
+    pub fn dialect(&self) -> AlpDialect {
+        match self {
+            Self::SpecV1_2(_) => AlpDialect::SpecV1_2,
+            Self::SubIotV0(_) => AlpDialect::SubIotV0,
+            Self::WizzilabV5_3(_) => AlpDialect::WizzilabV5_3,
+        }
+    }
+
+    pub fn encoded_size(&self) -> usize {
+        match self {
+            Self::SpecV1_2(cmd) => cmd.encoded_size(),
+            Self::SubIotV0(cmd) => cmd.encoded_size(),
+            Self::WizzilabV5_3(cmd) => cmd.encoded_size(),
+        }
+    }
+
+    pub fn encode(&self) -> Box<[u8]> {
+        match self {
+            Self::SpecV1_2(cmd) => cmd.encode(),
+            Self::SubIotV0(cmd) => cmd.encode(),
+            Self::WizzilabV5_3(cmd) => cmd.encode(),
+        }
+    }
+}
+impl std::fmt::Display for AnyCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::SpecV1_2(cmd) => write!(f, "{}", cmd),
+            Self::SubIotV0(cmd) => write!(f, "{}", cmd),
+            Self::WizzilabV5_3(cmd) => write!(f, "{}", cmd),
+        }
+    }
+}
+impl From<spec::Command> for AnyCommand {
+    fn from(cmd: spec::Command) -> Self {
+        Self::SpecV1_2(cmd)
+    }
+}
+impl From<sub_iot::Command> for AnyCommand {
+    fn from(cmd: sub_iot::Command) -> Self {
+        Self::SubIotV0(cmd)
+    }
+}
+impl From<wizzilab::Command> for AnyCommand {
+    fn from(cmd: wizzilab::Command) -> Self {
+        Self::WizzilabV5_3(cmd)
+    }
+}
+
+/// Decoding failure of any of the supported dialects
+#[derive(Clone, Debug, PartialEq)]
+pub enum AnyCommandParseFail {
+    SpecV1_2(spec::CommandParseFail),
+    SubIotV0(sub_iot::CommandParseFail),
+    WizzilabV5_3(wizzilab::CommandParseFail),
+}
+
+/// Decode a command using the parser of the given dialect.
+pub fn decode_any(
+    dialect: AlpDialect,
+    data: &[u8],
+) -> Result<AnyCommand, WithOffset<AnyCommandParseFail>> {
+    match dialect {
+        AlpDialect::SpecV1_2 => spec::Command::decode(data)
+            .map(AnyCommand::SpecV1_2)
+            .map_err(|e| e.map_value(AnyCommandParseFail::SpecV1_2)),
+        AlpDialect::SubIotV0 => sub_iot::Command::decode(data)
+            .map(AnyCommand::SubIotV0)
+            .map_err(|e| e.map_value(AnyCommandParseFail::SubIotV0)),
+        AlpDialect::WizzilabV5_3 => wizzilab::Command::decode(data)
+            .map(AnyCommand::WizzilabV5_3)
+            .map_err(|e| e.map_value(AnyCommandParseFail::WizzilabV5_3)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use hex_literal::hex;
+
+    #[cfg(feature = "actions")]
+    #[test]
+    fn test_decode_any() {
+        let data = hex!("B4 42   41 00 00 08");
+        for dialect in [
+            AlpDialect::SpecV1_2,
+            AlpDialect::SubIotV0,
+            AlpDialect::WizzilabV5_3,
+        ] {
+            let cmd = decode_any(dialect, &data).unwrap();
+            assert_eq!(cmd.dialect(), dialect);
+            assert_eq!(&cmd.encode()[..], &data[..]);
+        }
+        assert_eq!(
+            decode_any(AlpDialect::SpecV1_2, &data).unwrap(),
+            AnyCommand::SpecV1_2(spec::Command {
+                actions: vec![
+                    spec::Action::request_tag(true, 0x42),
+                    spec::Action::read_file_data(false, true, 0, 0, 8),
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_interface_configuration_display_prefix() {
+        let conf = spec::dash7::InterfaceConfiguration {
+            qos: spec::dash7::Qos {
+                retry: spec::dash7::RetryMode::No,
+                resp: spec::dash7::RespMode::Any,
+            },
+            to: spec::dash7::CompressedTime(0x23),
+            te: spec::dash7::CompressedTime(0x34),
+            nls_method: spec::dash7::NlsMethod::AesCcm32,
+            access_class: spec::dash7::AccessClass(0xFF),
+            address: spec::dash7::Address::Vid([0xAB, 0xCD]),
+            use_vid: false,
+            group_condition: spec::dash7::GroupCondition::Any,
+        };
+        let sub_iot_conf = sub_iot::dash7::InterfaceConfiguration {
+            qos: conf.qos,
+            to: conf.to,
+            access_class: conf.access_class.into(),
+            nls_method: conf.nls_method,
+            address: conf.address.clone(),
+        };
+        let wizzilab_conf = wizzilab::dash7::InterfaceConfiguration::from(conf.clone());
+
+        let common = "0X,to=12s|0xFF,NLS[7],VID[ABCD]";
+        assert_eq!(sub_iot_conf.to_string(), common);
+        assert_eq!(
+            conf.to_string(),
+            format!("{},te=80ms,use_vid=false,GCD=ANY", common)
+        );
+        assert_eq!(wizzilab_conf.to_string(), conf.to_string());
+    }
+
+    #[test]
+    fn test_decode_any_dialect_specific() {
+        // Opcode 5 is only known by the wizzilab dialect (WriteFileDataFlush)
+        let data = hex!("05 01 00 01 FF");
+        assert_eq!(
+            decode_any(AlpDialect::SpecV1_2, &data),
+            Err(WithOffset::new_head(AnyCommandParseFail::SpecV1_2(
+                spec::CommandParseFail {
+                    actions: vec![],
+                    error: spec::action::ActionDecodingError::UnknownOpCode(5),
+                }
+            )))
+        );
+        assert_eq!(
+            decode_any(AlpDialect::WizzilabV5_3, &data)
+                .unwrap()
+                .dialect(),
+            AlpDialect::WizzilabV5_3
+        );
+    }
+}
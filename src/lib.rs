@@ -15,6 +15,9 @@
 //! The goal of this library is to implement a specification with an emphasis on correctness, then
 //! on usability. Performance and memory usage are currently considered a secondary objective.
 //!
+//! The decoders never panic: whatever their input (truncated, corrupted or random bytes), they
+//! return either the decoded item or an error.
+//!
 //! Quickstart
 //! ==============================================================================
 //!
@@ -48,9 +51,28 @@
 //! let data = &hex!("B4 42   41 00 00 08   81 04 02 03  C0") as &[u8];
 //!
 //! assert_eq!(&cmd.encode()[..], data);
+//! # #[cfg(feature = "actions")]
+//! # {
 //! let parsed_cmd = Command::decode(data).expect("should be parsed without error");
 //! assert_eq!(parsed_cmd, cmd);
+//! # }
 //! ```
+//!
+//! Features
+//! ==============================================================================
+//! - `actions` (default): enables all the `action_*` features, each one enabling the decoding of
+//!   a family of `spec::v1_2` actions: `action_nop`, `action_read`, `action_write`,
+//!   `action_query`, `action_permission`, `action_file_management`, `action_response`,
+//!   `action_chunk`, `action_logic`, `action_forward` and `action_request_tag`. The actions of a
+//!   disabled family are decoded as unknown opcodes, but can still be built and encoded. These
+//!   features only apply to the `spec::v1_2` actions: the `sub_iot` and `wizzilab` dialects,
+//!   and the operands they share with `spec::v1_2`, always decode everything.
+//! - `defmt`: implements `defmt::Format` for the commands, actions and main operands, mirroring
+//!   their `Display` output.
+//! - `serde`: implements `serde::Serialize` and `serde::Deserialize` for the `spec::v1_2` items
+//!   and adds a JSON lines representation of its commands (`Command::to_ndjson_value`).
+//! - `tracing`: emits `trace` level `tracing` spans and events while decoding `spec::v1_2`
+//!   commands, holding the byte offset and opcode of each action.
 
 /// Implementation of the specification compliant Dash7 ALP protocol.
 pub mod spec;
@@ -66,5 +88,14 @@ pub mod wizzilab;
 /// A Codec module specifying how to encode/decode each encodable items
 pub mod codec;
 
+/// Dialect selection when the ALP flavour is only known at runtime
+pub mod dialect;
+pub use dialect::{decode_any, AlpDialect, AnyCommand};
+
+#[cfg(feature = "defmt")]
+mod defmt_format;
+
+#[cfg(test)]
+mod no_panic;
 #[cfg(test)]
 pub(crate) mod test_tools;
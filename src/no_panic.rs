@@ -0,0 +1,326 @@
+//! No panic guarantee of the decoders.
+//!
+//! The decoders are fed bytes coming from the outside world, which can be truncated, corrupted or
+//! crafted. Whatever their input, they must return either a value or an error, and never claim to
+//! have consumed more bytes than they were given (the command decoders rely on it to find the
+//! next action).
+//!
+//! Each decoder is checked against every truncation of known-good frames, random mutations of
+//! those frames and random buffers. The random generator is seeded with fixed values so that any
+//! failure is reproducible.
+use crate::{codec::Codec, spec::v1_2 as spec, sub_iot::v0 as sub_iot, wizzilab::v5_3 as wizzilab};
+use hex_literal::hex;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+const SEEDS: [u64; 3] = [0x0123_4567_89AB_CDEF, 0xDEAD_BEEF, 42];
+/// Number of mutations of each frame, and of random buffers, generated per seed
+const SAMPLES: usize = 256;
+const MAX_RANDOM_SIZE: usize = 64;
+
+/// Linear congruential generator: good enough to shake the decoders, without any dependency.
+struct Lcg(u64);
+impl Lcg {
+    fn byte(&mut self) -> u8 {
+        self.0 = self
+            .0
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (self.0 >> 33) as u8
+    }
+
+    /// Random number in `0..n`
+    fn below(&mut self, n: usize) -> usize {
+        let value = ((self.byte() as usize) << 8) | self.byte() as usize;
+        value % n
+    }
+}
+
+/// Decodes `data`, failing on panics and on decoders consuming more bytes than available.
+///
+/// `decode` returns the number of bytes consumed on success, if known.
+fn check_one(decode: &impl Fn(&[u8]) -> Option<usize>, data: &[u8]) {
+    let size = catch_unwind(AssertUnwindSafe(|| decode(data)))
+        .unwrap_or_else(|_| panic!("decoding panicked on {:02X?}", data));
+    if let Some(size) = size {
+        assert!(
+            size <= data.len(),
+            "{} bytes consumed out of {}: {:02X?}",
+            size,
+            data.len(),
+            data
+        );
+    }
+}
+
+/// Checks the decoding of every truncation `0..=len` of `frame`.
+pub(crate) fn check_truncations(decode: impl Fn(&[u8]) -> Option<usize>, frame: &[u8]) {
+    for len in 0..=frame.len() {
+        check_one(&decode, &frame[..len]);
+    }
+}
+
+/// Checks the decoding of the truncations of the frames, of their random mutations and of random
+/// buffers.
+fn check_decoder(decode: impl Fn(&[u8]) -> Option<usize>, frames: &[&[u8]]) {
+    for frame in frames {
+        check_truncations(&decode, frame);
+    }
+    for seed in SEEDS.iter() {
+        let mut rng = Lcg(*seed);
+        for frame in frames.iter().filter(|frame| !frame.is_empty()) {
+            for _ in 0..SAMPLES {
+                let mut data = frame.to_vec();
+                for _ in 0..1 + rng.below(3) {
+                    let i = rng.below(data.len());
+                    data[i] = rng.byte();
+                }
+                check_one(&decode, &data);
+            }
+        }
+        for _ in 0..SAMPLES {
+            let data: Vec<u8> = (0..rng.below(MAX_RANDOM_SIZE + 1))
+                .map(|_| rng.byte())
+                .collect();
+            check_one(&decode, &data);
+        }
+    }
+}
+
+/// Checks a `Codec` decoder, `frames` being complete encodings of items.
+fn check<T: Codec>(frames: &[&[u8]]) {
+    for frame in frames {
+        assert!(
+            matches!(T::decode(frame), Ok(v) if v.size == frame.len()),
+            "not a known-good frame: {:02X?}",
+            frame
+        );
+    }
+    check_decoder(|data| T::decode(data).ok().map(|v| v.size), frames)
+}
+
+// Frames of every action of each dialect
+const SPEC_ACTIONS: &[&[u8]] = &[
+    &hex!("80"),
+    &hex!("41 01 02 03"),
+    &hex!("02 09"),
+    &hex!("44 09 05 03 010203"),
+    &hex!("86 09 B8 13 01 02 DEADBEEF BAADFACE"),
+    &hex!("C8 00 04 05 06"),
+    &hex!("C9 38 03 000102 04 05"),
+    &hex!("CB 74 02 FFFF 04 05 08 09"),
+    &hex!("C8 81 02 0003 0020 01020304 00 04"),
+    &hex!("C8 F2 04 FF00FF00 01020304 00 04"),
+    &hex!("C8 91 01 03 20 00 04"),
+    &hex!("0A 01 42 0102030405060708"),
+    &hex!("17 42 24"),
+    &hex!("60 09 05 03 010203"),
+    &hex!("A1 09 B8 13 01 02 DEADBEEF BAADFACE"),
+    &hex!("22 02 F6"),
+    &hex!("62 D7 16 01 0123 02 03 04 B0 06 07 0800 0900 37 FF ABCD 0011223344"),
+    &hex!("A3 08"),
+    &hex!("B0"),
+    &hex!("F1"),
+    &hex!("72 00"),
+    &hex!("72 D7 02 23 34 37 FF ABCD"),
+    &hex!("F3 04 37 FF ABCD"),
+    &hex!("33 04"),
+    &hex!("B4 08"),
+];
+const SUB_IOT_ACTIONS: &[&[u8]] = &[
+    &hex!("80"),
+    &hex!("41 01 02 03"),
+    &hex!("44 09 05 03 010203"),
+    &hex!("86 09 B8 13 01 02 DEADBEEF BAADFACE"),
+    &hex!("C8 00 04 05 06"),
+    &hex!("22 02 F6"),
+    &hex!("72 00"),
+    &hex!("72 D7 02 23 37 FF ABCD"),
+    &hex!("F3 04 37 FF ABCD"),
+    &hex!("B4 08"),
+];
+const WIZZILAB_ACTIONS: &[&[u8]] = &[
+    &hex!("80"),
+    &hex!("41 01 02 03"),
+    &hex!("44 09 05 03 010203"),
+    &hex!("45 09 05 03 010203"),
+    &hex!("86 09 B8 13 01 02 DEADBEEF BAADFACE"),
+    &hex!("C8 00 04 05 06"),
+    &hex!("22 02 F6"),
+    &hex!("62 D7 1C 01 0123 02 03 04 B0 06 07 0800 0900 37 FF ABCD 000000000000 0011223344"),
+    &hex!("66 D7 16 01 0123 02 FF 04 05 06 0000 0807 36 FF 0011 000000000000"),
+    &hex!("72 00"),
+    &hex!("72 D7 02 23 34 37 FF ABCD 000000000000"),
+    &hex!("F3 04 37 FF ABCD 000000000000"),
+    &hex!("33 04"),
+    &hex!("36 FD 0004"),
+    &hex!("B6 FD 00000004"),
+    &hex!("B4 08"),
+];
+
+// Commands
+#[test]
+fn spec_command() {
+    let command = SPEC_ACTIONS.concat();
+    check_decoder(
+        |data| {
+            let _ = spec::Command::decode(data);
+            None
+        },
+        &[&command, &hex!("B4 42 41 00 00 08 81 04 02 03 C0")],
+    );
+}
+#[test]
+fn sub_iot_command() {
+    let command = SUB_IOT_ACTIONS.concat();
+    check_decoder(
+        |data| {
+            let _ = sub_iot::Command::decode(data);
+            None
+        },
+        &[&command],
+    );
+}
+#[test]
+fn wizzilab_command() {
+    let command = WIZZILAB_ACTIONS.concat();
+    check_decoder(
+        |data| {
+            let _ = wizzilab::Command::decode(data);
+            None
+        },
+        &[&command],
+    );
+}
+
+// Actions
+#[test]
+fn spec_action() {
+    // Only the actions enabled by the action_* features can be decoded
+    let supported = spec::action::supported_opcodes();
+    let frames: Vec<&[u8]> = SPEC_ACTIONS
+        .iter()
+        .copied()
+        .filter(|frame| supported.iter().any(|op| *op as u8 == frame[0] & 0x3F))
+        .collect();
+    check::<spec::Action>(&frames);
+}
+#[test]
+fn sub_iot_action() {
+    check::<sub_iot::Action>(SUB_IOT_ACTIONS);
+}
+#[test]
+fn wizzilab_action() {
+    check::<wizzilab::Action>(WIZZILAB_ACTIONS);
+}
+
+// Operands
+#[test]
+fn varint() {
+    check_decoder(
+        |data| spec::varint::decode(data).ok().map(|v| v.size),
+        &[
+            &hex!("3F"),
+            &hex!("7F FF"),
+            &hex!("BF FF FF"),
+            &hex!("FF FF FF FF"),
+        ],
+    );
+}
+#[test]
+fn file_offset() {
+    check::<spec::operand::FileOffset>(&[&hex!("02 7F FF")]);
+}
+#[test]
+fn file_header() {
+    check::<spec::data::FileHeader>(&[&hex!("B8 13 01 02 DEADBEEF BAADFACE")]);
+}
+#[test]
+fn query() {
+    check::<spec::operand::Query>(&[
+        &hex!("00 04 05 06"),
+        &hex!("38 03 000102 04 05"),
+        &hex!("41 03 090909 04 05"),
+        &hex!("74 02 FFFF 04 05 08 09"),
+        &hex!("81 02 0003 0020 01020304 00 04"),
+        &hex!("81 02 0100 0108 01 00 04"),
+        &hex!("91 01 03 20 00 04"),
+        &hex!("F2 04 FF00FF00 01020304 00 04"),
+    ]);
+}
+#[test]
+fn permission() {
+    check::<spec::operand::Permission>(&[&hex!("42 0102030405060708"), &hex!("12 02 ABCD")]);
+}
+#[test]
+fn action_status() {
+    check::<spec::operand::ActionStatus>(&[&hex!("02 F6")]);
+    check::<wizzilab::operand::ActionStatus>(&[&hex!("02 F6")]);
+}
+#[test]
+fn interface_configuration() {
+    check::<spec::operand::InterfaceConfiguration>(&[&hex!("00"), &hex!("D7 02 23 34 37 FF ABCD")]);
+    check::<sub_iot::operand::InterfaceConfiguration>(&[&hex!("00"), &hex!("D7 02 23 37 FF ABCD")]);
+    check::<wizzilab::operand::InterfaceConfiguration>(&[
+        &hex!("00"),
+        &hex!("D7 02 23 34 37 FF ABCD 000000000000"),
+    ]);
+}
+#[test]
+fn dash7_interface_configuration() {
+    check::<spec::dash7::InterfaceConfiguration>(&[
+        &hex!("02 23 34 37 FF ABCD"),
+        &hex!("02 23 34 26 48 0001020304050607"),
+    ]);
+    check::<sub_iot::dash7::InterfaceConfiguration>(&[
+        &hex!("02 23 37 FF ABCD"),
+        &hex!("02 23 26 48 0001020304050607"),
+    ]);
+    check::<wizzilab::dash7::InterfaceConfiguration>(&[
+        &hex!("02 23 34 37 FF ABCD 000000000000"),
+        &hex!("02 23 34 48 00 15"),
+    ]);
+}
+#[test]
+fn interface_status() {
+    check::<spec::operand::InterfaceStatus>(&[
+        &hex!("00 00"),
+        &hex!("D7 16 01 0123 02 03 04 B0 06 07 0800 0900 37 FF ABCD 0011223344"),
+    ]);
+    check::<wizzilab::operand::InterfaceStatus>(&[
+        &hex!("00 00"),
+        &hex!("D7 1C 01 0123 02 03 04 B0 06 07 0800 0900 37 FF ABCD 000000000000 0011223344"),
+    ]);
+}
+#[test]
+fn dash7_interface_status() {
+    check::<spec::dash7::InterfaceStatus>(&[&hex!(
+        "01 0123 02 03 04 B0 06 07 0800 0900 37 FF ABCD 0011223344"
+    )]);
+    check::<wizzilab::dash7::InterfaceStatus>(&[&hex!(
+        "01 0123 02 03 04 B0 06 07 0800 0900 37 FF ABCD 000000000000 0011223344"
+    )]);
+}
+#[test]
+fn interface_tx_status() {
+    check::<wizzilab::operand::InterfaceTxStatus>(&[
+        &hex!("00 00"),
+        &hex!("D7 16 01 0123 02 FF 04 05 06 0000 0807 36 FF 0011 000000000000"),
+    ]);
+    check::<wizzilab::dash7::interface_tx_status::InterfaceTxStatus>(&[&hex!(
+        "01 0123 02 FF 04 05 06 0000 0807 36 FF 0011 000000000000"
+    )]);
+}
+#[test]
+fn interface_final_status() {
+    check::<wizzilab::operand::InterfaceFinalStatus>(&[&hex!("00 02 FF")]);
+}
+#[test]
+fn indirect_interface() {
+    check::<spec::operand::NonOverloadedIndirectInterface>(&[&hex!("04")]);
+    check::<spec::operand::OverloadedIndirectInterface>(&[&hex!("04 37 FF ABCD")]);
+    check::<wizzilab::operand::OverloadedIndirectInterface>(&[&hex!("04 37 FF ABCD 000000000000")]);
+}
+#[test]
+fn flow() {
+    check::<wizzilab::action::Flow>(&[&hex!("36 FD 0004"), &hex!("B6 FD 00000004")]);
+}
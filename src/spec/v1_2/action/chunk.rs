@@ -4,6 +4,7 @@ use crate::{
 };
 
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Chunk {
     Continue = 0,
     Start = 1,
@@ -38,7 +39,7 @@ impl Codec for Chunk {
     }
     unsafe fn encode_in(&self, out: &mut [u8]) -> usize {
         out[0] = OpCode::Chunk as u8 + ((*self as u8) << 6);
-        1
+        super::checked_encoded_size!(self, 1)
     }
     fn decode(out: &[u8]) -> Result<WithSize<Self>, WithOffset<Self::Error>> {
         if out.is_empty() {
@@ -50,3 +51,4 @@ impl Codec for Chunk {
         })
     }
 }
+super::impl_encode_array!(Chunk, Chunk, 1);
@@ -5,7 +5,8 @@ use crate::codec::{Codec, StdError, WithOffset, WithSize};
 // overwrite the first part of the destination file?
 //
 // Wouldn't it be more appropriate to have 1 size and 2 file offsets?
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CopyFile {
     /// Group with next action
     pub group: bool,
@@ -27,3 +28,4 @@ impl std::fmt::Display for CopyFile {
     }
 }
 super::impl_simple_op!(CopyFile, group, resp, src_file_id, dst_file_id);
+super::impl_encode_array!(CopyFile, CopyFile, 3);
@@ -4,7 +4,8 @@ use crate::{
 };
 
 /// Write data to a file
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FileDataAction {
     /// Group with next action
     pub group: bool,
@@ -41,7 +42,7 @@ impl Codec for FileDataAction {
         offset += super::unsafe_varint_serialize!(out[2..], self.offset, self.data.len() as u32);
         out[offset..offset + self.data.len()].clone_from_slice(&self.data[..]);
         offset += self.data.len();
-        offset
+        super::checked_encoded_size!(self, offset)
     }
     fn decode(out: &[u8]) -> Result<WithSize<Self>, WithOffset<Self::Error>> {
         let min_size = 1 + 1 + 1 + 1;
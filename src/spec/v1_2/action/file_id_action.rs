@@ -2,7 +2,8 @@ use crate::codec::{Codec, StdError, WithOffset, WithSize};
 
 /// Checks whether a file exists
 // ALP_SPEC: How is the result of this command different from a read file of size 0?
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FileIdAction {
     /// Group with next action
     pub group: bool,
@@ -12,3 +13,29 @@ pub struct FileIdAction {
 }
 super::impl_display_simple_file_op!(FileIdAction, file_id);
 super::impl_simple_op!(FileIdAction, group, resp, file_id);
+impl FileIdAction {
+    /// Encodes the action as the `op_code` action (e.g. [ExistFile](super::OpCode::ExistFile)),
+    /// in an array rather than on the heap.
+    ///
+    /// `op_code` must be the opcode of an action holding a [FileIdAction]: debug builds panic
+    /// otherwise.
+    pub fn encode_array(&self, op_code: super::OpCode) -> [u8; 2] {
+        use super::OpCode;
+        debug_assert!(
+            matches!(
+                op_code,
+                OpCode::ReadFileProperties
+                    | OpCode::ExistFile
+                    | OpCode::DeleteFile
+                    | OpCode::RestoreFile
+                    | OpCode::FlushFile
+                    | OpCode::ExecuteFile
+            ),
+            "{} is not a file id action",
+            op_code
+        );
+        let mut out = [op_code as u8, 0];
+        unsafe { self.encode_in(&mut out) };
+        out
+    }
+}
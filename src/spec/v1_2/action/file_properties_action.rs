@@ -4,6 +4,7 @@ use crate::{
 };
 
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FilePropertiesAction {
     /// Group with next action
     pub group: bool,
@@ -25,3 +26,26 @@ impl std::fmt::Display for FilePropertiesAction {
         )
     }
 }
+#[test]
+fn test_file_properties_action_size() {
+    use crate::spec::v1_2::action::HeaderActionDecodingError;
+    use hex_literal::hex;
+    let data = hex!("C1 09   B8 13 01 02 00000020 00000040");
+    let WithSize { value, size } = FilePropertiesAction::decode(&data).unwrap();
+    assert_eq!(value.header.encoded_size(), 12);
+    assert_eq!(value.encoded_size(), 1 + 1 + value.header.encoded_size());
+    assert_eq!(size, data.len());
+    assert_eq!(
+        FilePropertiesAction::decode(&data[..10]),
+        Err(WithOffset::new(
+            2,
+            HeaderActionDecodingError::FileHeader(crate::codec::StdError::MissingBytes(4))
+        ))
+    );
+    assert_eq!(
+        FilePropertiesAction::decode(&data[..1]),
+        Err(WithOffset::new_head(
+            HeaderActionDecodingError::MissingBytes(1)
+        ))
+    );
+}
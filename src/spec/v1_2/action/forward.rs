@@ -4,6 +4,7 @@ use crate::{
 };
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Forward {
     // ALP_SPEC Ask for response ?
     pub resp: bool,
@@ -21,7 +22,8 @@ impl Codec for Forward {
     }
     unsafe fn encode_in(&self, out: &mut [u8]) -> usize {
         out[0] |= (self.resp as u8) << 6;
-        1 + self.conf.encode_in(&mut out[1..])
+        let size = 1 + self.conf.encode_in(&mut out[1..]);
+        super::checked_encoded_size!(self, size)
     }
     fn decode(out: &[u8]) -> Result<WithSize<Self>, WithOffset<Self::Error>> {
         let min_size = 1 + 1;
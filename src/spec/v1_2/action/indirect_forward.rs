@@ -4,6 +4,7 @@ use crate::{
 };
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IndirectForward {
     // ALP_SPEC Ask for response ?
     pub resp: bool,
@@ -30,7 +31,8 @@ impl Codec for IndirectForward {
             operand::IndirectInterface::NonOverloaded(_) => false,
         };
         out[0] |= ((overload as u8) << 7) | ((self.resp as u8) << 6);
-        1 + super::serialize_all!(&mut out[1..], &self.interface)
+        let size = 1 + super::serialize_all!(&mut out[1..], &self.interface);
+        super::checked_encoded_size!(self, size)
     }
     fn decode(out: &[u8]) -> Result<WithSize<Self>, WithOffset<Self::Error>> {
         if out.is_empty() {
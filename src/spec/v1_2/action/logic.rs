@@ -1,6 +1,7 @@
 use crate::codec::{Codec, StdError, WithOffset, WithSize};
 
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Logic {
     Or = 0,
     Xor = 1,
@@ -35,7 +36,7 @@ impl Codec for Logic {
     }
     unsafe fn encode_in(&self, out: &mut [u8]) -> usize {
         out[0] = crate::spec::v1_2::action::OpCode::Logic as u8 + ((*self as u8) << 6);
-        1
+        super::checked_encoded_size!(self, 1)
     }
     fn decode(out: &[u8]) -> Result<WithSize<Self>, WithOffset<Self::Error>> {
         if out.is_empty() {
@@ -47,3 +48,4 @@ impl Codec for Logic {
         })
     }
 }
+super::impl_encode_array!(Logic, Logic, 1);
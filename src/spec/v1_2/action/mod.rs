@@ -1,5 +1,7 @@
 #[cfg(test)]
-use crate::{spec::v1_2::dash7, test_tools::test_item};
+use crate::spec::v1_2::dash7;
+#[cfg(all(test, feature = "actions"))]
+use crate::test_tools::test_item;
 #[cfg(test)]
 use hex_literal::hex;
 
@@ -69,6 +71,38 @@ macro_rules! encoded_size {
 }
 pub(crate) use encoded_size;
 
+/// Checks in debug builds that an `encode_in` implementation wrote exactly as many bytes as
+/// announced by its `encoded_size`, then returns that size.
+macro_rules! checked_encoded_size {
+    ($self: expr, $size: expr) => {{
+        let size: usize = $size;
+        debug_assert_eq!(
+            size,
+            $self.encoded_size(),
+            "{}::encode_in wrote a different number of bytes than its encoded_size",
+            std::any::type_name::<Self>()
+        );
+        size
+    }};
+}
+pub(crate) use checked_encoded_size;
+
+/// Implements `encode_array` for an action of a fixed encoded size.
+macro_rules! impl_encode_array {
+    ($name: ident, $op_code: ident, $size: expr) => {
+        impl $name {
+            /// Encodes the action, opcode included, in an array rather than on the heap.
+            pub fn encode_array(&self) -> [u8; $size] {
+                let mut out = [0u8; $size];
+                out[0] = crate::spec::v1_2::action::OpCode::$op_code as u8;
+                unsafe { crate::codec::Codec::encode_in(self, &mut out) };
+                out
+            }
+        }
+    };
+}
+pub(crate) use impl_encode_array;
+
 macro_rules! impl_op_serialized {
     ($name: ident, $flag7: ident, $flag6: ident, $op1: ident, $op1_type: ty, $error: ty) => {
         impl crate::codec::Codec for $name {
@@ -78,7 +112,8 @@ macro_rules! impl_op_serialized {
             }
             unsafe fn encode_in(&self, out: &mut [u8]) -> usize {
                 out[0] |= ((self.$flag7 as u8) << 7) | ((self.$flag6 as u8) << 6);
-                1 + crate::spec::v1_2::action::serialize_all!(&mut out[1..], &self.$op1)
+                let size = 1 + crate::spec::v1_2::action::serialize_all!(&mut out[1..], &self.$op1);
+                crate::spec::v1_2::action::checked_encoded_size!(self, size)
             }
             fn decode(
                 out: &[u8],
@@ -172,7 +207,7 @@ macro_rules! impl_simple_op {
                     out[offset] = self.$x;
                     offset += 1;
                 })*
-                offset
+                crate::spec::v1_2::action::checked_encoded_size!(self, offset)
             }
             fn decode(out: &[u8]) -> Result<WithSize<Self>, WithOffset<Self::Error>> {
                 const SIZE: usize = 1 + crate::spec::v1_2::action::count!($( $x )*);
@@ -303,47 +338,58 @@ pub enum HeaderActionDecodingError {
     MissingBytes(usize),
     FileHeader(StdError),
 }
+impl HeaderActionDecodingError {
+    /// Minimum number of bytes missing to decode the item, if the error is caused by a
+    /// truncated input.
+    pub fn missing_bytes(&self) -> Option<usize> {
+        match self {
+            Self::MissingBytes(n) => Some(*n),
+            Self::FileHeader(e) => e.missing_bytes(),
+        }
+    }
+}
 
 macro_rules! impl_header_op {
     ($name: ident, $flag7: ident, $flag6: ident, $file_id: ident, $file_header: ident) => {
         impl Codec for $name {
             type Error = crate::spec::v1_2::action::HeaderActionDecodingError;
             fn encoded_size(&self) -> usize {
-                1 + 1 + 12
+                1 + 1 + self.$file_header.encoded_size()
             }
             unsafe fn encode_in(&self, out: &mut [u8]) -> usize {
                 out[0] |= ((self.$flag7 as u8) << 7) | ((self.$flag6 as u8) << 6);
                 out[1] = self.file_id;
                 let mut offset = 2;
                 offset += self.$file_header.encode_in(&mut out[offset..]);
-                offset
+                crate::spec::v1_2::action::checked_encoded_size!(self, offset)
             }
             fn decode(out: &[u8]) -> Result<WithSize<Self>, WithOffset<Self::Error>> {
-                const SIZE: usize = 1 + 1 + 12;
-                if (out.len() < SIZE) {
-                    Err(WithOffset::new(
-                        0,
-                        Self::Error::MissingBytes(SIZE - out.len()),
-                    ))
-                } else {
-                    let WithSize { value: header, .. } = data::FileHeader::decode(&out[2..])
-                        .map_err(|e| {
-                            let WithOffset { offset, value } = e;
-                            WithOffset {
-                                offset: offset + 2,
-                                value: Self::Error::FileHeader(value),
-                            }
-                        })?;
-                    Ok(WithSize {
-                        value: Self {
-                            $flag6: out[0] & 0x40 != 0,
-                            $flag7: out[0] & 0x80 != 0,
-                            $file_id: out[1],
-                            $file_header: header,
-                        },
-                        size: SIZE,
-                    })
+                if out.len() < 1 + 1 {
+                    return Err(WithOffset::new_head(Self::Error::MissingBytes(
+                        1 + 1 - out.len(),
+                    )));
                 }
+                let mut offset = 2;
+                let WithSize {
+                    value: header,
+                    size: header_size,
+                } = data::FileHeader::decode(&out[offset..]).map_err(|e| {
+                    let WithOffset { offset: off, value } = e;
+                    WithOffset {
+                        offset: offset + off,
+                        value: Self::Error::FileHeader(value),
+                    }
+                })?;
+                offset += header_size;
+                Ok(WithSize {
+                    value: Self {
+                        $flag6: out[0] & 0x40 != 0,
+                        $flag7: out[0] & 0x80 != 0,
+                        $file_id: out[1],
+                        $file_header: header,
+                    },
+                    size: offset,
+                })
             }
         }
     };
@@ -645,6 +691,113 @@ impl std::fmt::Display for OpCode {
     }
 }
 
+/// Opcodes of the actions enabled by the `action_*` features
+const SUPPORTED_OPCODES: &[OpCode] = &[
+    #[cfg(feature = "action_nop")]
+    OpCode::Nop,
+    #[cfg(feature = "action_read")]
+    OpCode::ReadFileData,
+    #[cfg(feature = "action_read")]
+    OpCode::ReadFileProperties,
+    #[cfg(feature = "action_write")]
+    OpCode::WriteFileData,
+    #[cfg(feature = "action_write")]
+    OpCode::WriteFileProperties,
+    #[cfg(feature = "action_query")]
+    OpCode::ActionQuery,
+    #[cfg(feature = "action_query")]
+    OpCode::BreakQuery,
+    #[cfg(feature = "action_permission")]
+    OpCode::PermissionRequest,
+    #[cfg(feature = "action_query")]
+    OpCode::VerifyChecksum,
+    #[cfg(feature = "action_file_management")]
+    OpCode::ExistFile,
+    #[cfg(feature = "action_file_management")]
+    OpCode::CreateNewFile,
+    #[cfg(feature = "action_file_management")]
+    OpCode::DeleteFile,
+    #[cfg(feature = "action_file_management")]
+    OpCode::RestoreFile,
+    #[cfg(feature = "action_file_management")]
+    OpCode::FlushFile,
+    #[cfg(feature = "action_file_management")]
+    OpCode::CopyFile,
+    #[cfg(feature = "action_file_management")]
+    OpCode::ExecuteFile,
+    #[cfg(feature = "action_response")]
+    OpCode::ReturnFileData,
+    #[cfg(feature = "action_response")]
+    OpCode::ReturnFileProperties,
+    #[cfg(feature = "action_response")]
+    OpCode::Status,
+    #[cfg(feature = "action_response")]
+    OpCode::ResponseTag,
+    #[cfg(feature = "action_chunk")]
+    OpCode::Chunk,
+    #[cfg(feature = "action_logic")]
+    OpCode::Logic,
+    #[cfg(feature = "action_forward")]
+    OpCode::Forward,
+    #[cfg(feature = "action_forward")]
+    OpCode::IndirectForward,
+    #[cfg(feature = "action_request_tag")]
+    OpCode::RequestTag,
+];
+
+/// Opcodes of the actions this build can decode, depending on the enabled `action_*` features.
+///
+/// [Extension](OpCode::Extension) actions are never supported, as their format is not defined.
+pub const fn supported_opcodes() -> &'static [OpCode] {
+    SUPPORTED_OPCODES
+}
+
+/// Whether actions of this opcode can be decoded by this build
+pub fn is_supported(op: OpCode) -> bool {
+    supported_opcodes().contains(&op)
+}
+
+/// Non conformant mapping of raw opcodes onto the parsers of known actions, to decode the near
+/// spec traffic of prototype devices (see
+/// [Command::decode_with_overrides](crate::spec::v1_2::Command::decode_with_overrides)).
+///
+/// The decoded actions are the standard actions: re-encoding them uses the opcode of the
+/// standard action, not the raw opcode they were decoded from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OpCodeMap {
+    map: [Option<OpCode>; 0x40],
+}
+impl Default for OpCodeMap {
+    fn default() -> Self {
+        Self { map: [None; 0x40] }
+    }
+}
+impl OpCodeMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decodes the actions of raw opcode `raw` as `op` actions.
+    ///
+    /// # Panics
+    /// Panics if `raw` does not fit in the 6 bits of an opcode.
+    pub fn with(mut self, raw: u8, op: OpCode) -> Self {
+        self.insert(raw, op);
+        self
+    }
+
+    /// See [with](#method.with)
+    pub fn insert(&mut self, raw: u8, op: OpCode) {
+        assert!(raw < 0x40, "opcode 0x{:02X} does not fit in 6 bits", raw);
+        self.map[raw as usize] = Some(op);
+    }
+
+    /// Opcode the actions of raw opcode `raw` are decoded as, if overridden
+    pub fn get(&self, raw: u8) -> Option<OpCode> {
+        self.map.get(raw as usize).copied().flatten()
+    }
+}
+
 // ===============================================================================
 // Actions
 // ===============================================================================
@@ -660,6 +813,8 @@ pub enum OperandValidationError {
 
 /// An ALP Action
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "op"))]
 pub enum Action {
     // Nop
     Nop(Nop),
@@ -741,6 +896,111 @@ impl Action {
             Self::RequestTag(_) => OpCode::RequestTag,
         }
     }
+
+    /// Whether the action is only sent by a responder.
+    ///
+    /// | Side     | Actions |
+    /// |----------|---------|
+    /// | Response | `ReturnFileData`, `ReturnFileProperties`, `Status`, `ResponseTag` |
+    /// | Request  | Read, write, query, permission and file management actions, `Forward`, `IndirectForward`, `RequestTag` |
+    /// | Both     | `Nop`, `Chunk`, `Logic` |
+    ///
+    /// The actions of the "both" row are neither requests nor responses.
+    pub fn is_response(&self) -> bool {
+        matches!(
+            self,
+            Self::ReturnFileData(_)
+                | Self::ReturnFileProperties(_)
+                | Self::Status(_)
+                | Self::ResponseTag(_)
+        )
+    }
+
+    /// Whether the action is only sent by a requester (see [is_response](#method.is_response)
+    /// for the classification).
+    pub fn is_request(&self) -> bool {
+        !self.is_response() && !matches!(self, Self::Nop(_) | Self::Chunk(_) | Self::Logic(_))
+    }
+
+    /// Opcode of the action the responder is expected to send back for this action.
+    ///
+    /// Read actions are answered by the matching `Return*` action and a `RequestTag` by a
+    /// `ResponseTag`. The other actions having a response flag are answered by a `Status` when the
+    /// flag is set. The forward, response, `Chunk` and `Logic` actions expect nothing.
+    pub fn expected_response(&self) -> Option<OpCode> {
+        let (resp, op_code) = match self {
+            Self::ReadFileData(ReadFileData { resp, .. }) => (*resp, OpCode::ReturnFileData),
+            Self::ReadFileProperties(FileIdAction { resp, .. }) => {
+                (*resp, OpCode::ReturnFileProperties)
+            }
+            Self::RequestTag(_) => (true, OpCode::ResponseTag),
+            Self::Nop(Nop { resp, .. })
+            | Self::WriteFileData(FileDataAction { resp, .. })
+            | Self::WriteFileProperties(FilePropertiesAction { resp, .. })
+            | Self::ActionQuery(QueryAction { resp, .. })
+            | Self::BreakQuery(QueryAction { resp, .. })
+            | Self::PermissionRequest(PermissionRequest { resp, .. })
+            | Self::VerifyChecksum(QueryAction { resp, .. })
+            | Self::ExistFile(FileIdAction { resp, .. })
+            | Self::CreateNewFile(FilePropertiesAction { resp, .. })
+            | Self::DeleteFile(FileIdAction { resp, .. })
+            | Self::RestoreFile(FileIdAction { resp, .. })
+            | Self::FlushFile(FileIdAction { resp, .. })
+            | Self::CopyFile(CopyFile { resp, .. })
+            | Self::ExecuteFile(FileIdAction { resp, .. }) => (*resp, OpCode::Status),
+            Self::ReturnFileData(_)
+            | Self::ReturnFileProperties(_)
+            | Self::Status(_)
+            | Self::ResponseTag(_)
+            | Self::Chunk(_)
+            | Self::Logic(_)
+            | Self::Forward(_)
+            | Self::IndirectForward(_) => (false, OpCode::Nop),
+        };
+        if resp {
+            Some(op_code)
+        } else {
+            None
+        }
+    }
+
+    /// Flag grouping this action with the next one, if the action has one.
+    pub fn group_mut(&mut self) -> Option<&mut bool> {
+        match self {
+            Self::Nop(Nop { group, .. })
+            | Self::ReadFileData(ReadFileData { group, .. })
+            | Self::ReadFileProperties(FileIdAction { group, .. })
+            | Self::WriteFileData(FileDataAction { group, .. })
+            | Self::WriteFileProperties(FilePropertiesAction { group, .. })
+            | Self::ActionQuery(QueryAction { group, .. })
+            | Self::BreakQuery(QueryAction { group, .. })
+            | Self::PermissionRequest(PermissionRequest { group, .. })
+            | Self::VerifyChecksum(QueryAction { group, .. })
+            | Self::ExistFile(FileIdAction { group, .. })
+            | Self::CreateNewFile(FilePropertiesAction { group, .. })
+            | Self::DeleteFile(FileIdAction { group, .. })
+            | Self::RestoreFile(FileIdAction { group, .. })
+            | Self::FlushFile(FileIdAction { group, .. })
+            | Self::CopyFile(CopyFile { group, .. })
+            | Self::ExecuteFile(FileIdAction { group, .. })
+            | Self::ReturnFileData(FileDataAction { group, .. })
+            | Self::ReturnFileProperties(FilePropertiesAction { group, .. }) => Some(group),
+            Self::Status(_)
+            | Self::ResponseTag(_)
+            | Self::Chunk(_)
+            | Self::Logic(_)
+            | Self::Forward(_)
+            | Self::IndirectForward(_)
+            | Self::RequestTag(_) => None,
+        }
+    }
+
+    /// Decodes the action at the head of `out`, returning it along with the bytes following
+    /// it.
+    pub fn decode_split(out: &[u8]) -> Result<(Self, &[u8]), WithOffset<ActionDecodingError>> {
+        let WithSize { value, size } = Self::decode(out)?;
+        Ok((value, &out[size..]))
+    }
 }
 
 impl std::fmt::Display for Action {
@@ -819,6 +1079,43 @@ pub enum ActionDecodingError {
     IndirectForward(StdError),
     RequestTag(StdError),
     Extension,
+    /// The command holds more actions than the decoder was allowed to decode
+    TooManyActions,
+}
+impl ActionDecodingError {
+    /// Minimum number of bytes missing to decode the item, if the error is caused by a
+    /// truncated input.
+    pub fn missing_bytes(&self) -> Option<usize> {
+        match self {
+            Self::NoData => Some(1),
+            Self::UnknownOpCode(_) | Self::Extension | Self::TooManyActions => None,
+            Self::Nop(e)
+            | Self::ReadFileData(e)
+            | Self::ReadFileProperties(e)
+            | Self::WriteFileData(e)
+            | Self::ExistFile(e)
+            | Self::DeleteFile(e)
+            | Self::RestoreFile(e)
+            | Self::FlushFile(e)
+            | Self::CopyFile(e)
+            | Self::ExecuteFile(e)
+            | Self::ReturnFileDataAction(e)
+            | Self::ResponseTag(e)
+            | Self::Chunk(e)
+            | Self::Logic(e)
+            | Self::IndirectForward(e)
+            | Self::RequestTag(e) => e.missing_bytes(),
+            Self::WriteFileProperties(e)
+            | Self::CreateNewFile(e)
+            | Self::ReturnFilePropertiesAction(e) => e.missing_bytes(),
+            Self::ActionQuery(e) | Self::BreakQuery(e) | Self::VerifyChecksum(e) => {
+                e.missing_bytes()
+            }
+            Self::PermissionRequest(e) => e.missing_bytes(),
+            Self::Status(e) => e.missing_bytes(),
+            Self::Forward(e) => e.missing_bytes(),
+        }
+    }
 }
 
 macro_rules! impl_std_error_map {
@@ -833,6 +1130,8 @@ macro_rules! impl_std_error_map {
     };
 }
 
+// The mappers of the actions disabled by the `action_*` features are unused
+#[cfg_attr(not(feature = "actions"), allow(dead_code))]
 impl ActionDecodingError {
     impl_std_error_map!(map_nop, Nop, StdError);
     impl_std_error_map!(map_read_file_data, ReadFileData, StdError);
@@ -953,83 +1252,128 @@ impl Codec for Action {
         let opcode = OpCode::from(out[0] & 0x3F)
             .map_err(Self::Error::UnknownOpCode)
             .map_err(WithOffset::new_head)?;
+        Self::decode_as(opcode, out)
+    }
+}
+impl Action {
+    /// Decodes the non empty `out` as an `opcode` action, ignoring the opcode bits of its first
+    /// byte.
+    #[cfg_attr(not(feature = "actions"), allow(unreachable_code, unused_variables))]
+    pub(crate) fn decode_as(
+        opcode: OpCode,
+        out: &[u8],
+    ) -> Result<WithSize<Self>, WithOffset<ActionDecodingError>> {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(?opcode, "decoding action");
         Ok(match opcode {
+            #[cfg(feature = "action_nop")]
             OpCode::Nop => Nop::decode(out)
                 .map_err(ActionDecodingError::map_nop)?
                 .map_value(Action::Nop),
+            #[cfg(feature = "action_read")]
             OpCode::ReadFileData => ReadFileData::decode(out)
                 .map_err(ActionDecodingError::map_read_file_data)?
                 .map_value(Action::ReadFileData),
+            #[cfg(feature = "action_read")]
             OpCode::ReadFileProperties => FileIdAction::decode(out)
                 .map_err(ActionDecodingError::map_read_file_properties)?
                 .map_value(Action::ReadFileProperties),
+            #[cfg(feature = "action_write")]
             OpCode::WriteFileData => FileDataAction::decode(out)
                 .map_err(ActionDecodingError::map_write_file_data)?
                 .map_value(Action::WriteFileData),
+            #[cfg(feature = "action_write")]
             OpCode::WriteFileProperties => FilePropertiesAction::decode(out)
                 .map_err(ActionDecodingError::map_write_file_properties)?
                 .map_value(Action::WriteFileProperties),
+            #[cfg(feature = "action_query")]
             OpCode::ActionQuery => QueryAction::decode(out)
                 .map_err(ActionDecodingError::map_action_query)?
                 .map_value(Action::ActionQuery),
+            #[cfg(feature = "action_query")]
             OpCode::BreakQuery => QueryAction::decode(out)
                 .map_err(ActionDecodingError::map_break_query)?
                 .map_value(Action::BreakQuery),
+            #[cfg(feature = "action_permission")]
             OpCode::PermissionRequest => PermissionRequest::decode(out)
                 .map_err(ActionDecodingError::map_permission_request)?
                 .map_value(Action::PermissionRequest),
+            #[cfg(feature = "action_query")]
             OpCode::VerifyChecksum => QueryAction::decode(out)
                 .map_err(ActionDecodingError::map_verify_checksum)?
                 .map_value(Action::VerifyChecksum),
+            #[cfg(feature = "action_file_management")]
             OpCode::ExistFile => FileIdAction::decode(out)
                 .map_err(ActionDecodingError::map_exist_file)?
                 .map_value(Action::ExistFile),
+            #[cfg(feature = "action_file_management")]
             OpCode::CreateNewFile => FilePropertiesAction::decode(out)
                 .map_err(ActionDecodingError::map_create_new_file)?
                 .map_value(Action::CreateNewFile),
+            #[cfg(feature = "action_file_management")]
             OpCode::DeleteFile => FileIdAction::decode(out)
                 .map_err(ActionDecodingError::map_delete_file)?
                 .map_value(Action::DeleteFile),
+            #[cfg(feature = "action_file_management")]
             OpCode::RestoreFile => FileIdAction::decode(out)
                 .map_err(ActionDecodingError::map_restore_file)?
                 .map_value(Action::RestoreFile),
+            #[cfg(feature = "action_file_management")]
             OpCode::FlushFile => FileIdAction::decode(out)
                 .map_err(ActionDecodingError::map_flush_file)?
                 .map_value(Action::FlushFile),
+            #[cfg(feature = "action_file_management")]
             OpCode::CopyFile => CopyFile::decode(out)
                 .map_err(ActionDecodingError::map_copy_file)?
                 .map_value(Action::CopyFile),
+            #[cfg(feature = "action_file_management")]
             OpCode::ExecuteFile => FileIdAction::decode(out)
                 .map_err(ActionDecodingError::map_execute_file)?
                 .map_value(Action::ExecuteFile),
+            #[cfg(feature = "action_response")]
             OpCode::ReturnFileData => FileDataAction::decode(out)
                 .map_err(ActionDecodingError::map_return_file_data)?
                 .map_value(Action::ReturnFileData),
+            #[cfg(feature = "action_response")]
             OpCode::ReturnFileProperties => FilePropertiesAction::decode(out)
                 .map_err(ActionDecodingError::map_return_file_properties)?
                 .map_value(Action::ReturnFileProperties),
+            #[cfg(feature = "action_response")]
             OpCode::Status => Status::decode(out)
                 .map_err(ActionDecodingError::map_status)?
                 .map_value(Action::Status),
+            #[cfg(feature = "action_response")]
             OpCode::ResponseTag => ResponseTag::decode(out)
                 .map_err(ActionDecodingError::map_response_tag)?
                 .map_value(Action::ResponseTag),
+            #[cfg(feature = "action_chunk")]
             OpCode::Chunk => Chunk::decode(out)
                 .map_err(ActionDecodingError::map_chunk)?
                 .map_value(Action::Chunk),
+            #[cfg(feature = "action_logic")]
             OpCode::Logic => Logic::decode(out)
                 .map_err(ActionDecodingError::map_logic)?
                 .map_value(Action::Logic),
+            #[cfg(feature = "action_forward")]
             OpCode::Forward => Forward::decode(out)
                 .map_err(ActionDecodingError::map_forward)?
                 .map_value(Action::Forward),
+            #[cfg(feature = "action_forward")]
             OpCode::IndirectForward => IndirectForward::decode(out)
                 .map_err(ActionDecodingError::map_indirect_forward)?
                 .map_value(Action::IndirectForward),
+            #[cfg(feature = "action_request_tag")]
             OpCode::RequestTag => RequestTag::decode(out)
                 .map_err(ActionDecodingError::map_request_tag)?
                 .map_value(Action::RequestTag),
             OpCode::Extension => return Err(WithOffset::new_head(ActionDecodingError::Extension)),
+            // Action disabled in this build
+            #[allow(unreachable_patterns)]
+            _ => {
+                return Err(WithOffset::new_head(ActionDecodingError::UnknownOpCode(
+                    opcode as u8,
+                )))
+            }
         })
     }
 }
@@ -1037,8 +1381,174 @@ impl Codec for Action {
 #[cfg(test)]
 mod test_codec {
     use super::*;
+    #[cfg(feature = "actions")]
     use crate::spec::v1_2::data;
 
+    crate::test_tools::opcode_coverage! {
+        #[cfg(feature = "actions")];
+    }
+
+    #[test]
+    fn request_response_classification() {
+        for n in 0..0x40 {
+            let action = match OpCode::from(n).ok().and_then(minimal_action) {
+                Some(action) => action,
+                None => continue,
+            };
+            let expected = match action.op_code() {
+                OpCode::ReturnFileData
+                | OpCode::ReturnFileProperties
+                | OpCode::Status
+                | OpCode::ResponseTag => (false, true),
+                OpCode::Nop | OpCode::Chunk | OpCode::Logic => (false, false),
+                _ => (true, false),
+            };
+            assert_eq!(
+                (action.is_request(), action.is_response()),
+                expected,
+                "{}",
+                action
+            );
+        }
+    }
+
+    #[test]
+    fn expected_response() {
+        assert_eq!(
+            Action::read_file_data(false, true, 1, 0, 8).expected_response(),
+            Some(OpCode::ReturnFileData)
+        );
+        assert_eq!(
+            Action::read_file_data(false, false, 1, 0, 8).expected_response(),
+            None
+        );
+        assert_eq!(
+            Action::read_file_properties(false, true, 1).expected_response(),
+            Some(OpCode::ReturnFileProperties)
+        );
+        assert_eq!(
+            Action::write_file_data(false, true, 1, 0, &[0u8][..]).expected_response(),
+            Some(OpCode::Status)
+        );
+        assert_eq!(
+            Action::write_file_data(false, false, 1, 0, &[0u8][..]).expected_response(),
+            None
+        );
+        assert_eq!(
+            Action::request_tag(true, 1).expected_response(),
+            Some(OpCode::ResponseTag)
+        );
+        assert_eq!(Action::nop(false, false).expected_response(), None);
+        assert_eq!(
+            Action::response_tag(true, false, 1).expected_response(),
+            None
+        );
+    }
+
+    /// Opcode values of the specification.
+    const OPCODE_TABLE: &[(OpCode, u8)] = &[
+        (OpCode::Nop, 0),
+        (OpCode::ReadFileData, 1),
+        (OpCode::ReadFileProperties, 2),
+        (OpCode::WriteFileData, 4),
+        (OpCode::WriteFileProperties, 6),
+        (OpCode::ActionQuery, 8),
+        (OpCode::BreakQuery, 9),
+        (OpCode::PermissionRequest, 10),
+        (OpCode::VerifyChecksum, 11),
+        (OpCode::ExistFile, 16),
+        (OpCode::CreateNewFile, 17),
+        (OpCode::DeleteFile, 18),
+        (OpCode::RestoreFile, 19),
+        (OpCode::FlushFile, 20),
+        (OpCode::CopyFile, 23),
+        (OpCode::ExecuteFile, 31),
+        (OpCode::ReturnFileData, 32),
+        (OpCode::ReturnFileProperties, 33),
+        (OpCode::Status, 34),
+        (OpCode::ResponseTag, 35),
+        (OpCode::Chunk, 48),
+        (OpCode::Logic, 49),
+        (OpCode::Forward, 50),
+        (OpCode::IndirectForward, 51),
+        (OpCode::RequestTag, 52),
+        (OpCode::Extension, 63),
+    ];
+
+    #[test]
+    fn opcode_discriminants() {
+        for (op_code, value) in OPCODE_TABLE.iter().copied() {
+            assert_eq!(op_code as u8, value, "{}", op_code);
+            assert_eq!(OpCode::from(value), Ok(op_code));
+        }
+        assert_eq!(
+            (0..=0xFF).filter(|n| OpCode::from(*n).is_ok()).count(),
+            OPCODE_TABLE.len()
+        );
+    }
+
+    #[test]
+    fn supported_opcodes_decode() {
+        for n in 0..0x40 {
+            let op_code = match OpCode::from(n) {
+                Ok(op_code) => op_code,
+                Err(_) => continue,
+            };
+            let decodes = minimal_action(op_code)
+                .is_some_and(|action| Action::decode(&action.encode()).is_ok());
+            assert_eq!(is_supported(op_code), decodes, "{}", op_code);
+        }
+        assert!(!is_supported(OpCode::Extension));
+        assert_eq!(
+            Action::decode(&[OpCode::Extension as u8]),
+            Err(WithOffset::new_head(ActionDecodingError::Extension))
+        );
+    }
+
+    #[cfg(feature = "actions")]
+    #[test]
+    fn query_builders() {
+        test_item(
+            Action::ActionQuery(QueryAction::compare_value(
+                4,
+                5,
+                operand::QueryComparisonType::Equal,
+                &[9, 9, 9],
+            )),
+            &hex!("08 41 03 090909 04 05"),
+        );
+        test_item(
+            Action::BreakQuery(QueryAction::in_range(0, 4, 3, 32)),
+            &hex!("09 91 01 03 20 00 04"),
+        );
+        test_item(
+            Action::VerifyChecksum(QueryAction::compare_value(
+                4,
+                5,
+                operand::QueryComparisonType::Equal,
+                &[0xA9, 0xA8],
+            )),
+            &hex!("0B 41 02 A9A8 04 05"),
+        );
+    }
+
+    #[cfg(feature = "actions")]
+    #[test]
+    fn decode_split() {
+        let data = hex!("41 01 02 03  C0");
+        let (action, tail) = Action::decode_split(&data).unwrap();
+        assert_eq!(action, Action::read_file_data(false, true, 1, 2, 3));
+        assert_eq!(tail, &hex!("C0"));
+        let (action, tail) = Action::decode_split(tail).unwrap();
+        assert_eq!(action, Action::nop(true, true));
+        assert!(tail.is_empty());
+        assert_eq!(
+            Action::decode_split(tail),
+            Err(WithOffset::new_head(ActionDecodingError::NoData))
+        );
+    }
+
+    #[cfg(feature = "actions")]
     #[test]
     fn nop() {
         test_item(
@@ -1049,6 +1559,7 @@ mod test_codec {
             &hex!("80"),
         )
     }
+    #[cfg(feature = "actions")]
     #[test]
     fn read_file_data() {
         test_item(
@@ -1065,6 +1576,7 @@ mod test_codec {
 
     macro_rules! impl_file_data_test {
         ($name: ident, $test_name: ident) => {
+            #[cfg(feature = "actions")]
             #[test]
             fn $test_name() {
                 test_item(
@@ -1089,6 +1601,7 @@ mod test_codec {
 
     macro_rules! impl_file_properties_test {
         ($name: ident, $test_name: ident) => {
+            #[cfg(feature = "actions")]
             #[test]
             fn $test_name() {
                 test_item(
@@ -1137,6 +1650,7 @@ mod test_codec {
 
     macro_rules! impl_query_test {
         ($name: ident, $test_name: ident) => {
+            #[cfg(feature = "actions")]
             #[test]
             fn $test_name() {
                 crate::test_tools::test_item(
@@ -1163,6 +1677,7 @@ mod test_codec {
     impl_query_test!(BreakQuery, break_query);
     impl_query_test!(VerifyChecksum, verify_checksum);
 
+    #[cfg(feature = "actions")]
     #[test]
     fn permission_request() {
         test_item(
@@ -1178,6 +1693,7 @@ mod test_codec {
 
     macro_rules! impl_file_id {
         ($name: ident, $test_name: ident) => {
+            #[cfg(feature = "actions")]
             #[test]
             fn $test_name() {
                 test_item(
@@ -1198,6 +1714,7 @@ mod test_codec {
     impl_file_id!(FlushFile, test_flush_file);
     impl_file_id!(ExecuteFile, test_execute_file);
 
+    #[cfg(feature = "actions")]
     #[test]
     fn copy_file() {
         test_item(
@@ -1211,6 +1728,7 @@ mod test_codec {
         )
     }
 
+    #[cfg(feature = "actions")]
     #[test]
     fn status() {
         test_item(
@@ -1222,6 +1740,7 @@ mod test_codec {
         )
     }
 
+    #[cfg(feature = "actions")]
     #[test]
     fn response_tag() {
         test_item(
@@ -1234,16 +1753,19 @@ mod test_codec {
         )
     }
 
+    #[cfg(feature = "actions")]
     #[test]
     fn chunk() {
         test_item(Action::Chunk(Chunk::End), &hex!("B0"))
     }
 
+    #[cfg(feature = "actions")]
     #[test]
     fn logic() {
         test_item(Action::Logic(Logic::Nand), &hex!("F1"))
     }
 
+    #[cfg(feature = "actions")]
     #[test]
     fn forward() {
         test_item(
@@ -1255,6 +1777,7 @@ mod test_codec {
         )
     }
 
+    #[cfg(feature = "actions")]
     #[test]
     fn indirect_forward() {
         test_item(
@@ -1270,9 +1793,73 @@ mod test_codec {
                 ),
             }),
             &hex!("F3   04   37 FF ABCD"),
-        )
+        );
+        test_item(
+            Action::IndirectForward(IndirectForward {
+                resp: false,
+                interface: operand::IndirectInterface::NonOverloaded(
+                    operand::NonOverloadedIndirectInterface {
+                        interface_file_id: 4,
+                    },
+                ),
+            }),
+            &hex!("33   04"),
+        );
+    }
+
+    #[test]
+    fn encode_array() {
+        let nop = Nop {
+            group: true,
+            resp: false,
+        };
+        assert_eq!(nop.encode_array(), hex!("80"));
+        let copy = CopyFile {
+            group: false,
+            resp: true,
+            src_file_id: 0x42,
+            dst_file_id: 0x24,
+        };
+        assert_eq!(copy.encode_array(), hex!("57 42 24"));
+        let file_id = FileIdAction {
+            group: false,
+            resp: false,
+            file_id: 9,
+        };
+        assert_eq!(file_id.encode_array(OpCode::ExistFile), hex!("10 09"));
+        assert_eq!(
+            &file_id.encode_array(OpCode::DeleteFile)[..],
+            &Action::DeleteFile(file_id).encode()[..]
+        );
+        for action in [
+            Action::Nop(nop),
+            Action::CopyFile(copy),
+            Action::request_tag(true, 8),
+            Action::response_tag(true, false, 8),
+            Action::Chunk(Chunk::End),
+            Action::Logic(Logic::Nand),
+        ] {
+            let array = match &action {
+                Action::Nop(x) => x.encode_array().to_vec(),
+                Action::CopyFile(x) => x.encode_array().to_vec(),
+                Action::RequestTag(x) => x.encode_array().to_vec(),
+                Action::ResponseTag(x) => x.encode_array().to_vec(),
+                Action::Chunk(x) => x.encode_array().to_vec(),
+                Action::Logic(x) => x.encode_array().to_vec(),
+                _ => unreachable!(),
+            };
+            assert_eq!(&array[..], &action.encode()[..], "{}", action);
+        }
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "CP is not a file id action")]
+    fn encode_array_bad_op_code() {
+        FileIdAction::default().encode_array(OpCode::CopyFile);
     }
 
+    #[cfg(feature = "actions")]
     #[test]
     fn request_tag() {
         test_item(
@@ -1696,7 +2283,7 @@ mod test_display {
                 seq: 7,
                 resp_to: 8,
                 fof: 9,
-                access_class: 0xFF,
+                access_class: dash7::AccessClass(0xFF),
                 address: dash7::Address::Vid([0xAB, 0xCD]),
                 nls_state: dash7::NlsState::AesCcm32(hex!("00 11 22 33 44")),
             }
@@ -1747,17 +2334,17 @@ mod test_display {
                         retry: dash7::RetryMode::No,
                         resp: dash7::RespMode::Any,
                     },
-                    to: 0x23,
-                    te: 0x34,
+                    to: dash7::CompressedTime(0x23),
+                    te: dash7::CompressedTime(0x34),
                     nls_method: dash7::NlsMethod::AesCcm32,
-                    access_class: 0xFF,
+                    access_class: dash7::AccessClass(0xFF),
                     address: dash7::Address::Vid([0xAB, 0xCD]),
                     use_vid: false,
                     group_condition: dash7::GroupCondition::Any,
                 }),
             })
             .to_string(),
-            "FWD[R]D7:0X,35,52|0xFF,use_vid=false,NLS[7],GCD=X,VID[ABCD]"
+            "FWD[R]D7:0X,to=12s|0xFF,NLS[7],VID[ABCD],te=80ms,use_vid=false,GCD=ANY"
         );
     }
 
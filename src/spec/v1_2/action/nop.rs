@@ -1,7 +1,8 @@
 use crate::codec::{Codec, StdError, WithOffset, WithSize};
 
 /// Does nothing
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Nop {
     /// Group with next action
     pub group: bool,
@@ -9,6 +10,7 @@ pub struct Nop {
     pub resp: bool,
 }
 super::impl_display_simple_op!(Nop);
+super::impl_encode_array!(Nop, Nop, 1);
 impl Codec for Nop {
     type Error = StdError;
 
@@ -17,7 +19,7 @@ impl Codec for Nop {
     }
     unsafe fn encode_in(&self, out: &mut [u8]) -> usize {
         out[0] |= ((self.group as u8) << 7) | ((self.resp as u8) << 6);
-        1
+        super::checked_encoded_size!(self, 1)
     }
     fn decode(out: &[u8]) -> Result<WithSize<Self>, WithOffset<Self::Error>> {
         if out.is_empty() {
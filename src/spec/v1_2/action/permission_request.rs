@@ -4,7 +4,8 @@ use crate::{
 };
 
 /// Request a level of permission using some permission type
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PermissionRequest {
     /// Group with next action
     pub group: bool,
@@ -23,11 +24,14 @@ impl Codec for PermissionRequest {
     unsafe fn encode_in(&self, out: &mut [u8]) -> usize {
         out[0] |= ((self.group as u8) << 7) | ((self.resp as u8) << 6);
         out[1] = self.level;
-        1 + super::serialize_all!(&mut out[2..], self.permission)
+        let size = 1 + 1 + super::serialize_all!(&mut out[2..], self.permission);
+        super::checked_encoded_size!(self, size)
     }
     fn decode(out: &[u8]) -> Result<WithSize<Self>, WithOffset<Self::Error>> {
-        if out.is_empty() {
-            Err(WithOffset::new_head(Self::Error::MissingBytes(1)))
+        if out.len() < 2 {
+            Err(WithOffset::new_head(Self::Error::MissingBytes(
+                2 - out.len(),
+            )))
         } else {
             let mut offset = 1;
             let level = out[offset];
@@ -2,6 +2,7 @@
 ///
 /// If the condition is not met, the next group of action should be skipped.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct QueryAction {
     /// Group with next action
     pub group: bool,
@@ -10,6 +11,32 @@ pub struct QueryAction {
     pub query: crate::spec::v1_2::operand::Query,
 }
 super::impl_display_simple_op!(QueryAction, query);
+/// Those helpers build the operand shared by the `ActionQuery`, `BreakQuery` and
+/// `VerifyChecksum` actions, the group and response flags being cleared.
+impl QueryAction {
+    /// See [Query::compare_value](crate::spec::v1_2::operand::Query::compare_value)
+    pub fn compare_value(
+        file_id: u8,
+        offset: u32,
+        comparison_type: super::operand::QueryComparisonType,
+        value: &[u8],
+    ) -> Self {
+        Self {
+            group: false,
+            resp: false,
+            query: super::operand::Query::compare_value(file_id, offset, comparison_type, value),
+        }
+    }
+
+    /// See [Query::in_range](crate::spec::v1_2::operand::Query::in_range)
+    pub fn in_range(file_id: u8, offset: u32, start: u32, stop: u32) -> Self {
+        Self {
+            group: false,
+            resp: false,
+            query: super::operand::Query::in_range(file_id, offset, start, stop),
+        }
+    }
+}
 super::impl_op_serialized!(
     QueryAction,
     group,
@@ -6,7 +6,8 @@ use crate::{
 use super::OperandValidationError;
 
 /// Read data from a file
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ReadFileData {
     /// Group with next action
     pub group: bool,
@@ -20,6 +21,25 @@ pub struct ReadFileData {
 }
 super::impl_display_simple_file_op!(ReadFileData, file_id, offset, size);
 impl ReadFileData {
+    /// Builds the action, checking that `offset` and `size` fit in a varint.
+    pub fn new(
+        group: bool,
+        resp: bool,
+        file_id: u8,
+        offset: u32,
+        size: u32,
+    ) -> Result<Self, OperandValidationError> {
+        let action = Self {
+            group,
+            resp,
+            file_id,
+            offset,
+            size,
+        };
+        action.validate()?;
+        Ok(action)
+    }
+
     pub fn validate(self) -> Result<(), OperandValidationError> {
         if self.offset > varint::MAX {
             return Err(OperandValidationError::OffsetTooBig);
@@ -39,7 +59,8 @@ impl Codec for ReadFileData {
     unsafe fn encode_in(&self, out: &mut [u8]) -> usize {
         out[0] |= ((self.group as u8) << 7) | ((self.resp as u8) << 6);
         out[1] = self.file_id;
-        1 + 1 + super::unsafe_varint_serialize!(out[2..], self.offset, self.size)
+        let size = 1 + 1 + super::unsafe_varint_serialize!(out[2..], self.offset, self.size);
+        super::checked_encoded_size!(self, size)
     }
     fn decode(out: &[u8]) -> Result<WithSize<Self>, WithOffset<Self::Error>> {
         let min_size = 1 + 1 + 1 + 1;
@@ -56,18 +77,12 @@ impl Codec for ReadFileData {
         let WithSize {
             value: offset,
             size: offset_size,
-        } = varint::decode(&out[off..]).map_err(|e| {
-            e.shift(off);
-            e
-        })?;
+        } = varint::decode(&out[off..]).map_err(|e| e.shift(off))?;
         off += offset_size;
         let WithSize {
             value: size,
             size: size_size,
-        } = varint::decode(&out[off..]).map_err(|e| {
-            e.shift(off);
-            e
-        })?;
+        } = varint::decode(&out[off..]).map_err(|e| e.shift(off))?;
         off += size_size;
         Ok(WithSize {
             value: Self {
@@ -81,3 +96,41 @@ impl Codec for ReadFileData {
         })
     }
 }
+#[test]
+fn test_read_file_data_default() {
+    assert_eq!(
+        ReadFileData {
+            file_id: 5,
+            size: 8,
+            ..Default::default()
+        },
+        ReadFileData {
+            group: false,
+            resp: false,
+            file_id: 5,
+            offset: 0,
+            size: 8,
+        }
+    );
+}
+#[test]
+fn test_read_file_data_new() {
+    assert_eq!(
+        ReadFileData::new(true, true, 1, varint::MAX, varint::MAX),
+        Ok(ReadFileData {
+            group: true,
+            resp: true,
+            file_id: 1,
+            offset: (1 << 30) - 1,
+            size: (1 << 30) - 1,
+        })
+    );
+    assert_eq!(
+        ReadFileData::new(false, true, 1, 1 << 30, 8),
+        Err(OperandValidationError::OffsetTooBig)
+    );
+    assert_eq!(
+        ReadFileData::new(false, true, 1, 0, 1 << 30),
+        Err(OperandValidationError::SizeTooBig)
+    );
+}
@@ -1,6 +1,7 @@
 use crate::codec::{Codec, StdError, WithOffset, WithSize};
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RequestTag {
     /// Ask for end of packet
     ///
@@ -22,7 +23,7 @@ impl Codec for RequestTag {
     unsafe fn encode_in(&self, out: &mut [u8]) -> usize {
         out[0] |= (self.eop as u8) << 7;
         out[1] = self.id;
-        1 + 1
+        super::checked_encoded_size!(self, 1 + 1)
     }
     fn decode(out: &[u8]) -> Result<WithSize<Self>, WithOffset<Self::Error>> {
         let min_size = 1 + 1;
@@ -41,3 +42,4 @@ impl Codec for RequestTag {
         })
     }
 }
+super::impl_encode_array!(RequestTag, RequestTag, 2);
@@ -1,6 +1,7 @@
 use crate::codec::{Codec, StdError, WithOffset, WithSize};
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ResponseTag {
     /// End of packet
     ///
@@ -24,3 +25,4 @@ impl std::fmt::Display for ResponseTag {
     }
 }
 super::impl_simple_op!(ResponseTag, eop, err, id);
+super::impl_encode_array!(ResponseTag, ResponseTag, 2);
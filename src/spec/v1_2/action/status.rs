@@ -19,6 +19,7 @@ impl StatusType {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Status {
     // ALP SPEC: This is named status, but it should be named action status compared to the '2'
     // other statuses.
@@ -41,6 +42,18 @@ pub enum StatusDecodingError {
     Action(operand::ActionStatusDecodingError),
     Interface(operand::InterfaceStatusDecodingError),
 }
+impl StatusDecodingError {
+    /// Minimum number of bytes missing to decode the item, if the error is caused by a
+    /// truncated input.
+    pub fn missing_bytes(&self) -> Option<usize> {
+        match self {
+            Self::MissingBytes(n) => Some(*n),
+            Self::UnknownType(_) => None,
+            Self::Action(e) => e.missing_bytes(),
+            Self::Interface(e) => e.missing_bytes(),
+        }
+    }
+}
 impl Codec for Status {
     type Error = StatusDecodingError;
     fn encoded_size(&self) -> usize {
@@ -56,10 +69,11 @@ impl Codec for Status {
         } as u8)
             << 6;
         let out = &mut out[1..];
-        1 + match self {
+        let size = 1 + match self {
             Status::Action(op) => op.encode_in(out),
             Status::Interface(op) => op.encode_in(out),
-        }
+        };
+        super::checked_encoded_size!(self, size)
     }
     fn decode(out: &[u8]) -> Result<WithSize<Self>, WithOffset<Self::Error>> {
         if out.is_empty() {
@@ -0,0 +1,700 @@
+//! Property based round trip tests of the codec.
+//!
+//! Every action and operand gets an `Arbitrary` implementation that only generates values
+//! respecting the constraints of the specification (varint ranges, mask and value sizes
+//! matching the announced size, ...), so that any encodable value is covered.
+use crate::spec::v1_2::{
+    action::{
+        Chunk, CopyFile, FileDataAction, FileIdAction, FilePropertiesAction, Forward,
+        IndirectForward, Logic, Nop, PermissionRequest, QueryAction, ReadFileData, RequestTag,
+        ResponseTag, Status,
+    },
+    dash7, data, operand, varint, Action, Codec, Command,
+};
+use proptest::prelude::*;
+
+macro_rules! impl_arbitrary {
+    ($name: ty, $strategy: expr) => {
+        impl Arbitrary for $name {
+            type Parameters = ();
+            type Strategy = BoxedStrategy<Self>;
+            fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+                $strategy.boxed()
+            }
+        }
+    };
+}
+
+fn varint() -> impl Strategy<Value = u32> {
+    prop_oneof![0..=0x3Fu32, 0..=0x3F_FFu32, 0..=varint::MAX]
+}
+
+fn bytes(max: usize) -> impl Strategy<Value = Box<[u8]>> {
+    prop::collection::vec(any::<u8>(), 0..=max).prop_map(Vec::into_boxed_slice)
+}
+
+fn bytes_exact(size: usize) -> impl Strategy<Value = Box<[u8]>> {
+    prop::collection::vec(any::<u8>(), size).prop_map(Vec::into_boxed_slice)
+}
+
+fn optional_mask(size: usize) -> impl Strategy<Value = Option<Box<[u8]>>> {
+    prop::option::of(bytes_exact(size))
+}
+
+// Data
+impl_arbitrary!(
+    data::UserPermissions,
+    (any::<bool>(), any::<bool>(), any::<bool>()).prop_map(|(read, write, run)| Self {
+        read,
+        write,
+        run
+    })
+);
+impl_arbitrary!(
+    data::Permissions,
+    (
+        any::<bool>(),
+        any::<bool>(),
+        any::<data::UserPermissions>(),
+        any::<data::UserPermissions>()
+    )
+        .prop_map(|(encrypted, executable, user, guest)| Self {
+            encrypted,
+            executable,
+            user,
+            guest,
+        })
+);
+impl_arbitrary!(
+    data::ActionCondition,
+    prop_oneof![
+        Just(Self::List),
+        Just(Self::Read),
+        Just(Self::Write),
+        Just(Self::WriteFlush),
+        Just(Self::Unknown4),
+        Just(Self::Unknown5),
+        Just(Self::Unknown6),
+        Just(Self::Unknown7),
+    ]
+);
+impl_arbitrary!(
+    data::StorageClass,
+    prop_oneof![
+        Just(Self::Transient),
+        Just(Self::Volatile),
+        Just(Self::Restorable),
+        Just(Self::Permanent),
+    ]
+);
+impl_arbitrary!(
+    data::FileProperties,
+    (
+        any::<bool>(),
+        any::<data::ActionCondition>(),
+        any::<data::StorageClass>()
+    )
+        .prop_map(|(act_en, act_cond, storage_class)| Self {
+            act_en,
+            act_cond,
+            storage_class,
+        })
+);
+impl_arbitrary!(
+    data::FileHeader,
+    (
+        any::<data::Permissions>(),
+        any::<data::FileProperties>(),
+        any::<u8>(),
+        any::<u8>(),
+        any::<u32>(),
+        any::<u32>()
+    )
+        .prop_map(
+            |(
+                permissions,
+                properties,
+                alp_cmd_fid,
+                interface_file_id,
+                file_size,
+                allocated_size,
+            )| {
+                Self {
+                    permissions,
+                    properties,
+                    alp_cmd_fid,
+                    interface_file_id,
+                    file_size,
+                    allocated_size,
+                }
+            }
+        )
+);
+
+// Dash7
+impl_arbitrary!(
+    dash7::NlsMethod,
+    prop_oneof![
+        Just(Self::None),
+        Just(Self::AesCtr),
+        Just(Self::AesCbcMac128),
+        Just(Self::AesCbcMac64),
+        Just(Self::AesCbcMac32),
+        Just(Self::AesCcm128),
+        Just(Self::AesCcm64),
+        Just(Self::AesCcm32),
+    ]
+);
+impl_arbitrary!(
+    dash7::NlsState,
+    prop_oneof![
+        Just(Self::None),
+        any::<[u8; 5]>().prop_map(Self::AesCtr),
+        any::<[u8; 5]>().prop_map(Self::AesCbcMac128),
+        any::<[u8; 5]>().prop_map(Self::AesCbcMac64),
+        any::<[u8; 5]>().prop_map(Self::AesCbcMac32),
+        any::<[u8; 5]>().prop_map(Self::AesCcm128),
+        any::<[u8; 5]>().prop_map(Self::AesCcm64),
+        any::<[u8; 5]>().prop_map(Self::AesCcm32),
+    ]
+);
+impl_arbitrary!(
+    dash7::Address,
+    prop_oneof![
+        any::<u8>().prop_map(Self::NbId),
+        Just(Self::NoId),
+        any::<[u8; 8]>().prop_map(Self::Uid),
+        any::<[u8; 2]>().prop_map(Self::Vid),
+    ]
+);
+impl_arbitrary!(
+    dash7::RetryMode,
+    prop_oneof![
+        Just(Self::No),
+        Just(Self::Rfu1),
+        Just(Self::Rfu2),
+        Just(Self::Rfu3),
+        Just(Self::Rfu4),
+        Just(Self::Rfu5),
+        Just(Self::Rfu6),
+        Just(Self::Rfu7),
+    ]
+);
+impl_arbitrary!(
+    dash7::RespMode,
+    prop_oneof![
+        Just(Self::No),
+        Just(Self::All),
+        Just(Self::Any),
+        Just(Self::RespNoRpt),
+        Just(Self::RespOnData),
+        Just(Self::RespPreferred),
+    ]
+);
+impl_arbitrary!(
+    dash7::Qos,
+    (any::<dash7::RetryMode>(), any::<dash7::RespMode>())
+        .prop_map(|(retry, resp)| Self { retry, resp })
+);
+impl_arbitrary!(
+    dash7::GroupCondition,
+    prop_oneof![
+        Just(Self::Any),
+        Just(Self::NotEqual),
+        Just(Self::Equal),
+        Just(Self::GreaterThan),
+    ]
+);
+impl_arbitrary!(dash7::AccessClass, any::<u8>().prop_map(Self));
+impl_arbitrary!(
+    dash7::InterfaceConfiguration,
+    (
+        any::<dash7::Qos>(),
+        any::<u8>(),
+        any::<u8>(),
+        any::<dash7::AccessClass>(),
+        any::<dash7::NlsMethod>(),
+        any::<dash7::Address>(),
+        any::<bool>(),
+        any::<dash7::GroupCondition>()
+    )
+        .prop_map(
+            |(qos, to, te, access_class, nls_method, address, use_vid, group_condition)| Self {
+                qos,
+                to: dash7::CompressedTime(to),
+                te: dash7::CompressedTime(te),
+                access_class,
+                nls_method,
+                address,
+                use_vid,
+                group_condition,
+            }
+        )
+);
+impl_arbitrary!(
+    dash7::InterfaceStatus,
+    (
+        (
+            any::<u8>(),
+            any::<u16>(),
+            any::<u8>(),
+            any::<u8>(),
+            any::<u8>(),
+            any::<u8>(),
+            any::<u8>()
+        ),
+        (
+            any::<u8>(),
+            any::<u16>(),
+            any::<u16>(),
+            any::<dash7::AccessClass>(),
+            any::<dash7::Address>(),
+            any::<dash7::NlsState>()
+        )
+    )
+        .prop_map(
+            |(
+                (ch_header, ch_idx, rxlev, lb, snr, status, token),
+                (seq, resp_to, fof, access_class, address, nls_state),
+            )| Self {
+                ch_header,
+                ch_idx,
+                rxlev,
+                lb,
+                snr,
+                status,
+                token,
+                seq,
+                resp_to,
+                fof,
+                access_class,
+                address,
+                nls_state,
+            }
+        )
+);
+
+// Operands
+impl_arbitrary!(
+    operand::FileOffset,
+    (any::<u8>(), varint()).prop_map(|(id, offset)| Self { id, offset })
+);
+impl_arbitrary!(
+    operand::StatusCode,
+    prop_oneof![
+        Just(Self::Received),
+        Just(Self::Ok),
+        Just(Self::FileIdMissing),
+        Just(Self::CreateFileIdAlreadyExist),
+        Just(Self::FileIsNotRestorable),
+        Just(Self::InsufficientPermission),
+        Just(Self::CreateFileLengthOverflow),
+        Just(Self::CreateFileAllocationOverflow),
+        Just(Self::WriteOffsetOverflow),
+        Just(Self::WriteDataOverflow),
+        Just(Self::WriteStorageUnavailable),
+        Just(Self::UnknownOperation),
+        Just(Self::OperandIncomplete),
+        Just(Self::OperandWrongFormat),
+        Just(Self::UnknownError),
+    ]
+);
+impl_arbitrary!(
+    operand::ActionStatus,
+    (any::<u8>(), any::<operand::StatusCode>())
+        .prop_map(|(action_id, status)| Self { action_id, status })
+);
+impl_arbitrary!(
+    operand::InterfaceStatus,
+    prop_oneof![
+        Just(Self::Host),
+        any::<dash7::InterfaceStatus>().prop_map(Self::D7asp),
+        (
+            any::<u8>().prop_filter("known interface id", |id| *id != 0 && *id != 0xD7),
+            bytes(16)
+        )
+            .prop_map(|(id, data)| Self::Unknown(operand::InterfaceStatusUnknown { id, data })),
+    ]
+);
+impl_arbitrary!(
+    operand::InterfaceConfiguration,
+    prop_oneof![
+        Just(Self::Host),
+        any::<dash7::InterfaceConfiguration>().prop_map(Self::D7asp),
+    ]
+);
+// The non overloaded indirect interface can not be decoded without knowing the interface.
+impl_arbitrary!(
+    operand::IndirectInterface,
+    (
+        any::<u8>(),
+        any::<dash7::NlsMethod>(),
+        any::<u8>(),
+        any::<dash7::Address>()
+    )
+        .prop_map(|(interface_file_id, nls_method, access_class, address)| {
+            Self::Overloaded(operand::OverloadedIndirectInterface {
+                interface_file_id,
+                nls_method,
+                access_class,
+                address,
+            })
+        })
+);
+impl_arbitrary!(
+    operand::Permission,
+    prop_oneof![
+        any::<[u8; 8]>().prop_map(Self::Dash7),
+        (
+            any::<u8>().prop_filter("dash7 permission id", |id| *id != 0x42),
+            bytes(16)
+        )
+            .prop_map(|(id, data)| Self::Rfu(id, data)),
+    ]
+);
+impl_arbitrary!(
+    operand::QueryComparisonType,
+    prop_oneof![
+        Just(Self::Inequal),
+        Just(Self::Equal),
+        Just(Self::LessThan),
+        Just(Self::LessThanOrEqual),
+        Just(Self::GreaterThan),
+        Just(Self::GreaterThanOrEqual),
+    ]
+);
+impl_arbitrary!(
+    operand::QueryRangeComparisonType,
+    prop_oneof![Just(Self::NotInRange), Just(Self::InRange)]
+);
+impl_arbitrary!(
+    operand::NonVoid,
+    (varint(), any::<operand::FileOffset>()).prop_map(|(size, file)| Self { size, file })
+);
+impl_arbitrary!(
+    operand::ComparisonWithZero,
+    (
+        any::<bool>(),
+        any::<operand::QueryComparisonType>(),
+        0..16usize,
+        any::<operand::FileOffset>()
+    )
+        .prop_flat_map(|(signed_data, comparison_type, size, file)| {
+            optional_mask(size).prop_map(move |mask| Self {
+                signed_data,
+                comparison_type,
+                size: size as u32,
+                mask,
+                file,
+            })
+        })
+);
+impl_arbitrary!(
+    operand::ComparisonWithValue,
+    (
+        any::<bool>(),
+        any::<operand::QueryComparisonType>(),
+        0..16usize,
+        any::<operand::FileOffset>()
+    )
+        .prop_flat_map(|(signed_data, comparison_type, size, file)| {
+            (optional_mask(size), bytes_exact(size)).prop_map(move |(mask, value)| Self {
+                signed_data,
+                comparison_type,
+                size: size as u32,
+                mask,
+                value,
+                file,
+            })
+        })
+);
+impl_arbitrary!(
+    operand::ComparisonWithOtherFile,
+    (
+        any::<bool>(),
+        any::<operand::QueryComparisonType>(),
+        0..16usize,
+        any::<operand::FileOffset>(),
+        any::<operand::FileOffset>()
+    )
+        .prop_flat_map(|(signed_data, comparison_type, size, file1, file2)| {
+            optional_mask(size).prop_map(move |mask| Self {
+                signed_data,
+                comparison_type,
+                size: size as u32,
+                mask,
+                file1,
+                file2,
+            })
+        })
+);
+impl_arbitrary!(
+    operand::BitmapRangeComparison,
+    (
+        any::<bool>(),
+        any::<operand::QueryRangeComparisonType>(),
+        1..=4u32,
+        any::<operand::FileOffset>()
+    )
+        .prop_flat_map(|(signed_data, comparison_type, size, file)| {
+            let max = u32::MAX >> (8 * (4 - size));
+            // Keep the bitmap reasonably small
+            (Just(size), 0..=max, 0..=256u32).prop_flat_map(move |(size, start, len)| {
+                let stop = start.saturating_add(len).min(max);
+                let bitmap_size = operand::BitmapRangeComparison::bitmap_size(start, stop);
+                optional_mask(bitmap_size).prop_map(move |mask| Self {
+                    signed_data,
+                    comparison_type,
+                    size,
+                    start,
+                    stop,
+                    mask,
+                    file,
+                })
+            })
+        })
+);
+impl_arbitrary!(
+    operand::StringTokenSearch,
+    (0..8u8, 0..16usize, any::<operand::FileOffset>()).prop_flat_map(|(max_errors, size, file)| {
+        (optional_mask(size), bytes_exact(size)).prop_map(move |(mask, value)| Self {
+            max_errors,
+            size: size as u32,
+            mask,
+            value,
+            file,
+        })
+    })
+);
+impl_arbitrary!(
+    operand::Query,
+    prop_oneof![
+        any::<operand::NonVoid>().prop_map(Self::NonVoid),
+        any::<operand::ComparisonWithZero>().prop_map(Self::ComparisonWithZero),
+        any::<operand::ComparisonWithValue>().prop_map(Self::ComparisonWithValue),
+        any::<operand::ComparisonWithOtherFile>().prop_map(Self::ComparisonWithOtherFile),
+        any::<operand::BitmapRangeComparison>().prop_map(Self::BitmapRangeComparison),
+        any::<operand::StringTokenSearch>().prop_map(Self::StringTokenSearch),
+    ]
+);
+
+// Actions
+impl_arbitrary!(
+    Nop,
+    (any::<bool>(), any::<bool>()).prop_map(|(group, resp)| Self { group, resp })
+);
+impl_arbitrary!(
+    ReadFileData,
+    (
+        any::<bool>(),
+        any::<bool>(),
+        any::<u8>(),
+        varint(),
+        varint()
+    )
+        .prop_map(|(group, resp, file_id, offset, size)| Self {
+            group,
+            resp,
+            file_id,
+            offset,
+            size,
+        })
+);
+impl_arbitrary!(
+    FileIdAction,
+    (any::<bool>(), any::<bool>(), any::<u8>()).prop_map(|(group, resp, file_id)| Self {
+        group,
+        resp,
+        file_id,
+    })
+);
+impl_arbitrary!(
+    FileDataAction,
+    (
+        any::<bool>(),
+        any::<bool>(),
+        any::<u8>(),
+        varint(),
+        bytes(32)
+    )
+        .prop_map(|(group, resp, file_id, offset, data)| Self {
+            group,
+            resp,
+            file_id,
+            offset,
+            data,
+        })
+);
+impl_arbitrary!(
+    FilePropertiesAction,
+    (
+        any::<bool>(),
+        any::<bool>(),
+        any::<u8>(),
+        any::<data::FileHeader>()
+    )
+        .prop_map(|(group, resp, file_id, header)| Self {
+            group,
+            resp,
+            file_id,
+            header,
+        })
+);
+impl_arbitrary!(
+    QueryAction,
+    (any::<bool>(), any::<bool>(), any::<operand::Query>())
+        .prop_map(|(group, resp, query)| { Self { group, resp, query } })
+);
+impl_arbitrary!(
+    PermissionRequest,
+    (
+        any::<bool>(),
+        any::<bool>(),
+        any::<u8>(),
+        any::<operand::Permission>()
+    )
+        .prop_map(|(group, resp, level, permission)| Self {
+            group,
+            resp,
+            level,
+            permission,
+        })
+);
+impl_arbitrary!(
+    CopyFile,
+    (any::<bool>(), any::<bool>(), any::<u8>(), any::<u8>()).prop_map(
+        |(group, resp, src_file_id, dst_file_id)| Self {
+            group,
+            resp,
+            src_file_id,
+            dst_file_id,
+        }
+    )
+);
+impl_arbitrary!(
+    Status,
+    prop_oneof![
+        any::<operand::ActionStatus>().prop_map(Self::Action),
+        any::<operand::InterfaceStatus>().prop_map(Self::Interface),
+    ]
+);
+impl_arbitrary!(
+    ResponseTag,
+    (any::<bool>(), any::<bool>(), any::<u8>()).prop_map(|(eop, err, id)| Self { eop, err, id })
+);
+impl_arbitrary!(
+    Chunk,
+    prop_oneof![
+        Just(Self::Continue),
+        Just(Self::Start),
+        Just(Self::End),
+        Just(Self::StartEnd),
+    ]
+);
+impl_arbitrary!(
+    Logic,
+    prop_oneof![
+        Just(Self::Or),
+        Just(Self::Xor),
+        Just(Self::Nor),
+        Just(Self::Nand),
+    ]
+);
+impl_arbitrary!(
+    Forward,
+    (any::<bool>(), any::<operand::InterfaceConfiguration>())
+        .prop_map(|(resp, conf)| Self { resp, conf })
+);
+impl_arbitrary!(
+    IndirectForward,
+    (any::<bool>(), any::<operand::IndirectInterface>())
+        .prop_map(|(resp, interface)| Self { resp, interface })
+);
+impl_arbitrary!(
+    RequestTag,
+    (any::<bool>(), any::<u8>()).prop_map(|(eop, id)| Self { eop, id })
+);
+impl_arbitrary!(
+    Action,
+    prop_oneof![
+        any::<Nop>().prop_map(Self::Nop),
+        any::<ReadFileData>().prop_map(Self::ReadFileData),
+        any::<FileIdAction>().prop_map(Self::ReadFileProperties),
+        any::<FileDataAction>().prop_map(Self::WriteFileData),
+        any::<FilePropertiesAction>().prop_map(Self::WriteFileProperties),
+        any::<QueryAction>().prop_map(Self::ActionQuery),
+        any::<QueryAction>().prop_map(Self::BreakQuery),
+        any::<PermissionRequest>().prop_map(Self::PermissionRequest),
+        any::<QueryAction>().prop_map(Self::VerifyChecksum),
+        any::<FileIdAction>().prop_map(Self::ExistFile),
+        any::<FilePropertiesAction>().prop_map(Self::CreateNewFile),
+        any::<FileIdAction>().prop_map(Self::DeleteFile),
+        any::<FileIdAction>().prop_map(Self::RestoreFile),
+        any::<FileIdAction>().prop_map(Self::FlushFile),
+        any::<CopyFile>().prop_map(Self::CopyFile),
+        any::<FileIdAction>().prop_map(Self::ExecuteFile),
+        any::<FileDataAction>().prop_map(Self::ReturnFileData),
+        any::<FilePropertiesAction>().prop_map(Self::ReturnFileProperties),
+        any::<Status>().prop_map(Self::Status),
+        any::<ResponseTag>().prop_map(Self::ResponseTag),
+        any::<Chunk>().prop_map(Self::Chunk),
+        any::<Logic>().prop_map(Self::Logic),
+        any::<Forward>().prop_map(Self::Forward),
+        any::<IndirectForward>().prop_map(Self::IndirectForward),
+        any::<RequestTag>().prop_map(Self::RequestTag),
+    ]
+);
+impl_arbitrary!(
+    Command,
+    prop::collection::vec(any::<Action>(), 0..8).prop_map(|actions| Self { actions })
+);
+
+fn check_round_trip<T>(item: &T)
+where
+    T: Codec + PartialEq + std::fmt::Debug,
+    T::Error: std::fmt::Debug,
+{
+    let mut encoded = vec![0u8; item.encoded_size()].into_boxed_slice();
+    assert_eq!(unsafe { item.encode_in(&mut encoded) }, encoded.len());
+    let decoded = T::decode(&encoded).expect("should decode its own encoding");
+    assert_eq!(decoded.size, encoded.len());
+    assert_eq!(&decoded.value, item);
+    assert_eq!(decoded.value.encode(), encoded);
+}
+
+proptest! {
+    #[cfg(feature = "actions")]
+    #[test]
+    fn round_trip_action(action in any::<Action>()) {
+        check_round_trip(&action);
+    }
+
+    #[cfg(feature = "actions")]
+    #[test]
+    fn round_trip_command(command in any::<Command>()) {
+        let encoded = command.encode();
+        let decoded = Command::decode(&encoded).expect("should decode its own encoding");
+        prop_assert_eq!(&decoded, &command);
+        prop_assert_eq!(decoded.encode(), encoded);
+    }
+
+    #[test]
+    fn round_trip_query(query in any::<operand::Query>()) {
+        check_round_trip(&query);
+    }
+
+    #[test]
+    fn round_trip_interface_status(status in any::<operand::InterfaceStatus>()) {
+        check_round_trip(&status);
+    }
+
+    #[test]
+    fn round_trip_interface_configuration(conf in any::<operand::InterfaceConfiguration>()) {
+        check_round_trip(&conf);
+    }
+
+    #[test]
+    fn round_trip_file_header(header in any::<data::FileHeader>()) {
+        check_round_trip(&header);
+    }
+}
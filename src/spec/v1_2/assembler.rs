@@ -0,0 +1,251 @@
+use crate::spec::v1_2::{action::FileDataAction, Action, Command};
+use std::ops::Range;
+
+/// Reason a chunk was refused by a [`FileDataAssembler`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AssemblyError {
+    /// The chunk belongs to another file
+    WrongFileId { expected: u8, actual: u8 },
+    /// The chunk goes past the assembled range
+    OutOfRange { chunk: Range<u32> },
+    /// The chunk overlaps already received data with different bytes, starting at `offset`
+    InconsistentOverlap { offset: u32 },
+}
+impl std::fmt::Display for AssemblyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::WrongFileId { expected, actual } => {
+                write!(f, "chunk of file {} instead of {}", actual, expected)
+            }
+            Self::OutOfRange { chunk } => {
+                write!(f, "chunk {:?} out of the assembled range", chunk)
+            }
+            Self::InconsistentOverlap { offset } => {
+                write!(
+                    f,
+                    "chunk contradicts the received data at offset {}",
+                    offset
+                )
+            }
+        }
+    }
+}
+
+/// Rebuilds a file range read in pieces from the `ReturnFileData` chunks answering the reads.
+///
+/// The chunks can be fed in any order. Overlapping chunks are accepted as long as they agree on
+/// the overlapping bytes.
+///
+/// The buffer grows with the received chunks: the size of the range, which may come from the
+/// wire, is not allocated upfront.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileDataAssembler {
+    file_id: u8,
+    range: Range<u32>,
+    /// Bytes of the range up to the end of the furthest received chunk
+    data: Vec<u8>,
+    /// Received file ranges, sorted and merged
+    received: Vec<Range<u32>>,
+}
+impl FileDataAssembler {
+    /// Assembler of the `range` bytes of the file `file_id`
+    pub fn new(file_id: u8, range: Range<u32>) -> Self {
+        Self {
+            file_id,
+            range,
+            data: vec![],
+            received: vec![],
+        }
+    }
+
+    pub fn file_id(&self) -> u8 {
+        self.file_id
+    }
+
+    /// Adds a chunk of data of the file.
+    ///
+    /// On error the assembler is left unchanged.
+    pub fn push(&mut self, chunk: &FileDataAction) -> Result<(), AssemblyError> {
+        if chunk.file_id != self.file_id {
+            return Err(AssemblyError::WrongFileId {
+                expected: self.file_id,
+                actual: chunk.file_id,
+            });
+        }
+        let start = chunk.offset;
+        let end = start as u64 + chunk.data.len() as u64;
+        if start < self.range.start || end > self.range.end as u64 {
+            return Err(AssemblyError::OutOfRange {
+                chunk: start..end.min(u32::MAX as u64) as u32,
+            });
+        }
+        let end = end as u32;
+        if start == end {
+            return Ok(());
+        }
+
+        for received in self.received.iter() {
+            let overlap = received.start.max(start)..received.end.min(end);
+            for offset in overlap {
+                if self.data[(offset - self.range.start) as usize]
+                    != chunk.data[(offset - start) as usize]
+                {
+                    return Err(AssemblyError::InconsistentOverlap { offset });
+                }
+            }
+        }
+
+        let base = (start - self.range.start) as usize;
+        if self.data.len() < base + chunk.data.len() {
+            self.data.resize(base + chunk.data.len(), 0);
+        }
+        self.data[base..base + chunk.data.len()].copy_from_slice(&chunk.data);
+        self.insert_range(start..end);
+        Ok(())
+    }
+
+    /// Adds all the `ReturnFileData` chunks of this file found in the command, ignoring the other
+    /// actions.
+    ///
+    /// The chunks preceding a failing one stay added.
+    pub fn push_command(&mut self, command: &Command) -> Result<(), AssemblyError> {
+        for action in command.actions.iter() {
+            if let Action::ReturnFileData(chunk) = action {
+                if chunk.file_id == self.file_id {
+                    self.push(chunk)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn insert_range(&mut self, mut range: Range<u32>) {
+        let mut merged = Vec::with_capacity(self.received.len() + 1);
+        for received in self.received.drain(..) {
+            if received.end < range.start || range.end < received.start {
+                merged.push(received);
+            } else {
+                range = received.start.min(range.start)..received.end.max(range.end);
+            }
+        }
+        let index = merged
+            .iter()
+            .position(|received| received.start > range.start)
+            .unwrap_or(merged.len());
+        merged.insert(index, range);
+        self.received = merged;
+    }
+
+    /// File ranges not received yet, in file order.
+    pub fn missing_ranges(&self) -> Vec<Range<u32>> {
+        let mut missing = vec![];
+        let mut cursor = self.range.start;
+        for received in self.received.iter() {
+            if received.start > cursor {
+                missing.push(cursor..received.start);
+            }
+            cursor = received.end;
+        }
+        if cursor < self.range.end {
+            missing.push(cursor..self.range.end);
+        }
+        missing
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.missing_ranges().is_empty()
+    }
+
+    /// Assembled bytes of the range, up to the furthest received byte, the missing bytes being
+    /// zeroed.
+    ///
+    /// Once the assembly [is complete](#method.is_complete), this is the whole range.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn chunk(file_id: u8, offset: u32, data: &[u8]) -> FileDataAction {
+        FileDataAction {
+            group: false,
+            resp: false,
+            file_id,
+            offset,
+            data: data.into(),
+        }
+    }
+
+    #[test]
+    fn test_assemble() {
+        let mut assembler = FileDataAssembler::new(3, 10..20);
+        assert_eq!(assembler.missing_ranges(), vec![10..20]);
+
+        assembler.push(&chunk(3, 10, &[0, 1, 2])).unwrap();
+        assert_eq!(assembler.data(), &[0, 1, 2]);
+        assembler.push(&chunk(3, 16, &[6, 7])).unwrap();
+        assert_eq!(assembler.data(), &[0, 1, 2, 0, 0, 0, 6, 7]);
+        assert_eq!(assembler.missing_ranges(), vec![13..16, 18..20]);
+        assert!(!assembler.is_complete());
+
+        // Consistent overlap joining two received ranges
+        assembler
+            .push_command(&Command {
+                actions: vec![
+                    Action::response_tag(true, false, 1),
+                    Action::ReturnFileData(chunk(4, 0, &[0xFF])),
+                    Action::ReturnFileData(chunk(3, 12, &[2, 3, 4, 5, 6])),
+                ],
+            })
+            .unwrap();
+        assert_eq!(assembler.missing_ranges(), vec![18..20]);
+
+        assembler.push(&chunk(3, 18, &[8, 9])).unwrap();
+        assert!(assembler.is_complete());
+        assert_eq!(assembler.into_data(), vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn test_assemble_errors() {
+        let mut assembler = FileDataAssembler::new(3, 10..20);
+        assembler.push(&chunk(3, 12, &[2, 3])).unwrap();
+        let before = assembler.clone();
+
+        assert_eq!(
+            assembler.push(&chunk(4, 12, &[2, 3])),
+            Err(AssemblyError::WrongFileId {
+                expected: 3,
+                actual: 4
+            })
+        );
+        assert_eq!(
+            assembler.push(&chunk(3, 18, &[8, 9, 10])),
+            Err(AssemblyError::OutOfRange { chunk: 18..21 })
+        );
+        assert_eq!(
+            assembler.push(&chunk(3, 8, &[0, 0, 0])),
+            Err(AssemblyError::OutOfRange { chunk: 8..11 })
+        );
+        assert_eq!(
+            assembler.push(&chunk(3, 11, &[1, 2, 0xFF])),
+            Err(AssemblyError::InconsistentOverlap { offset: 13 })
+        );
+        assert_eq!(assembler, before);
+    }
+
+    #[test]
+    fn test_huge_range() {
+        // Nothing is allocated for the parts of the range not received yet
+        let mut assembler = FileDataAssembler::new(3, 0..u32::MAX);
+        assert!(assembler.data().is_empty());
+        assembler.push(&chunk(3, 2, &[2, 3])).unwrap();
+        assert_eq!(assembler.data(), &[0, 0, 2, 3]);
+    }
+}
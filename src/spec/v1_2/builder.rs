@@ -0,0 +1,297 @@
+use super::{
+    action::{FileDataAction, Forward, QueryAction},
+    dash7, operand, varint, Action, Command,
+};
+#[cfg(test)]
+use hex_literal::hex;
+use std::ops::RangeInclusive;
+
+/// Helper to build a [Command](super::Command) action after action.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CommandBuilder {
+    actions: Vec<Action>,
+}
+
+impl CommandBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends an action to the command
+    pub fn action(mut self, action: Action) -> Self {
+        self.actions.push(action);
+        self
+    }
+
+    /// Writes data to a file and flushes it.
+    ///
+    /// The specification does not define a "write and flush" action, so this emits a
+    /// `WriteFileData` grouped with the `FlushFile` of the same file.
+    pub fn write_and_flush(mut self, file_id: u8, offset: u32, data: &[u8]) -> Self {
+        self.actions.push(Action::WriteFileData(FileDataAction {
+            group: true,
+            resp: false,
+            file_id,
+            offset,
+            data: data.into(),
+        }));
+        self.actions
+            .push(Action::test_flush_file(false, false, file_id));
+        self
+    }
+
+    /// Checks the existence of each file of `range`, each check asking for its status.
+    pub fn exist_files(mut self, range: RangeInclusive<u8>) -> Self {
+        self.actions
+            .extend(FileIdRange(range).exist_files(false, true));
+        self
+    }
+
+    /// Forwards the following actions to the device of unique id `uid`.
+    ///
+    /// The Dash7 interface configuration uses defaults suited to a single target: no retry,
+    /// waiting for a response (`RespMode::Any`), no flush or execution delay and no security.
+    /// They can be changed with [with_qos](#method.with_qos) or by building the
+    /// [Forward] by hand.
+    pub fn forward_to_uid(self, uid: [u8; 8], access_class: u8) -> Self {
+        self.forward_to(dash7::Address::Uid(uid), access_class)
+    }
+
+    /// Forwards the following actions to the device of virtual id `vid` (see
+    /// [forward_to_uid](#method.forward_to_uid) for the configuration used).
+    pub fn forward_to_vid(self, vid: [u8; 2], access_class: u8) -> Self {
+        self.forward_to(dash7::Address::Vid(vid), access_class)
+    }
+
+    fn forward_to(mut self, address: dash7::Address, access_class: u8) -> Self {
+        self.actions.push(Action::Forward(Forward {
+            resp: true,
+            conf: operand::InterfaceConfiguration::D7asp(dash7::InterfaceConfiguration {
+                qos: dash7::Qos {
+                    retry: dash7::RetryMode::No,
+                    resp: dash7::RespMode::Any,
+                },
+                to: dash7::CompressedTime(0),
+                te: dash7::CompressedTime(0),
+                access_class: dash7::AccessClass(access_class),
+                nls_method: dash7::NlsMethod::None,
+                address,
+                use_vid: false,
+                group_condition: dash7::GroupCondition::Any,
+            }),
+        }));
+        self
+    }
+
+    /// Overrides the QoS of the Dash7 [Forward] added last.
+    /// # Panics
+    /// Panics if the last action is not a Dash7 forward.
+    pub fn with_qos(mut self, qos: dash7::Qos) -> Self {
+        match self.actions.last_mut() {
+            Some(Action::Forward(Forward {
+                conf: operand::InterfaceConfiguration::D7asp(conf),
+                ..
+            })) => conf.qos = qos,
+            _ => panic!("with_qos must follow a Dash7 forward"),
+        }
+        self
+    }
+
+    /// Reads the `total_size` first bytes of a file, with as few `ReadFileData` as possible.
+    ///
+    /// Each read asks for at most `max_chunk` bytes (and at most [varint::MAX], the biggest
+    /// size an action can request), the reads being consecutive.
+    /// # Panics
+    /// Panics if a read would start at an offset not encodable in a varint (bigger than
+    /// [varint::MAX]).
+    pub fn read_whole_file(mut self, file_id: u8, total_size: u32, max_chunk: Option<u32>) -> Self {
+        let chunk = max_chunk.unwrap_or(varint::MAX).clamp(1, varint::MAX);
+        let mut offset = 0;
+        while offset < total_size {
+            assert!(offset <= varint::MAX, "read offset {} too big", offset);
+            let size = chunk.min(total_size - offset);
+            self.actions
+                .push(Action::read_file_data(false, true, file_id, offset, size));
+            offset += size;
+        }
+        self
+    }
+
+    /// Checks that the CRC16 of the content of a file matches `expected_crc16`, with a
+    /// `VerifyChecksum` comparing the checksum computed from `offset` with the big endian
+    /// value.
+    ///
+    /// The region end cannot be given: a value comparison query only carries the length of the
+    /// compared value, which is the 2 bytes of the CRC.
+    /// # Panics
+    /// Panics if `offset` is not encodable in a varint (bigger than [varint::MAX]).
+    pub fn verify_file_crc(mut self, file_id: u8, offset: u32, expected_crc16: u16) -> Self {
+        assert!(offset <= varint::MAX, "checksum offset {} too big", offset);
+        self.actions
+            .push(Action::VerifyChecksum(QueryAction::compare_value(
+                file_id,
+                offset,
+                operand::QueryComparisonType::Equal,
+                &expected_crc16.to_be_bytes(),
+            )));
+        self
+    }
+
+    pub fn build(self) -> Command {
+        Command {
+            actions: self.actions,
+        }
+    }
+}
+
+/// Inclusive range of file ids, to apply a file id action to each of its files.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileIdRange(pub RangeInclusive<u8>);
+impl FileIdRange {
+    /// One action per file id of the range, built by `action` (e.g.
+    /// `|id| Action::test_flush_file(false, false, id)`).
+    pub fn actions<F: FnMut(u8) -> Action>(&self, action: F) -> Vec<Action> {
+        self.0.clone().map(action).collect()
+    }
+
+    pub fn exist_files(&self, group: bool, resp: bool) -> Vec<Action> {
+        self.actions(|id| Action::test_exist_file(group, resp, id))
+    }
+
+    pub fn delete_files(&self, group: bool, resp: bool) -> Vec<Action> {
+        self.actions(|id| Action::test_delete_file(group, resp, id))
+    }
+}
+impl From<RangeInclusive<u8>> for FileIdRange {
+    fn from(range: RangeInclusive<u8>) -> Self {
+        Self(range)
+    }
+}
+
+#[test]
+fn test_write_and_flush() {
+    let cmd = CommandBuilder::new()
+        .write_and_flush(4, 2, &hex!("0102"))
+        .build();
+    assert_eq!(
+        cmd.actions,
+        vec![
+            Action::write_file_data(true, false, 4, 2, &hex!("0102")[..]),
+            Action::test_flush_file(false, false, 4),
+        ]
+    );
+    assert_eq!(&cmd.encode()[..], &hex!("84 04 02 02 0102  14 04"));
+}
+
+#[test]
+fn test_exist_files() {
+    let cmd = CommandBuilder::new().exist_files(0x40..=0x42).build();
+    assert_eq!(
+        cmd.actions,
+        vec![
+            Action::test_exist_file(false, true, 0x40),
+            Action::test_exist_file(false, true, 0x41),
+            Action::test_exist_file(false, true, 0x42),
+        ]
+    );
+    assert_eq!(&cmd.encode()[..], &hex!("50 40  50 41  50 42"));
+    assert_eq!(
+        FileIdRange::from(0xFF..=0xFF).delete_files(true, false),
+        vec![Action::test_delete_file(true, false, 0xFF)]
+    );
+    #[allow(clippy::reversed_empty_ranges)]
+    let empty = FileIdRange(3..=2);
+    assert!(empty.exist_files(false, false).is_empty());
+}
+
+#[test]
+fn test_read_whole_file() {
+    let cmd = CommandBuilder::new()
+        .read_whole_file(3, 100, Some(40))
+        .build();
+    assert_eq!(
+        cmd.actions,
+        vec![
+            Action::read_file_data(false, true, 3, 0, 40),
+            Action::read_file_data(false, true, 3, 40, 40),
+            Action::read_file_data(false, true, 3, 80, 20),
+        ]
+    );
+    assert_eq!(
+        CommandBuilder::new()
+            .read_whole_file(3, 100, None)
+            .build()
+            .actions,
+        vec![Action::read_file_data(false, true, 3, 0, 100)]
+    );
+    assert_eq!(
+        CommandBuilder::new()
+            .read_whole_file(3, varint::MAX + 2, None)
+            .build()
+            .actions,
+        vec![
+            Action::read_file_data(false, true, 3, 0, varint::MAX),
+            Action::read_file_data(false, true, 3, varint::MAX, 2),
+        ]
+    );
+    assert!(CommandBuilder::new()
+        .read_whole_file(3, 0, Some(40))
+        .build()
+        .actions
+        .is_empty());
+}
+
+#[test]
+fn test_verify_file_crc() {
+    let cmd = CommandBuilder::new()
+        .verify_file_crc(9, 0x10, 0xBEEF)
+        .build();
+    assert_eq!(
+        cmd.actions,
+        vec![Action::VerifyChecksum(QueryAction::compare_value(
+            9,
+            0x10,
+            operand::QueryComparisonType::Equal,
+            &hex!("BEEF"),
+        ))]
+    );
+    assert_eq!(&cmd.encode()[..], &hex!("0B  41 02 BEEF 09 10"));
+}
+
+#[test]
+#[should_panic]
+fn test_verify_file_crc_offset_too_big() {
+    CommandBuilder::new().verify_file_crc(9, varint::MAX + 1, 0xBEEF);
+}
+
+#[test]
+fn test_forward_to() {
+    let cmd = CommandBuilder::new()
+        .forward_to_uid(hex!("0011223344556677"), 0x01)
+        .action(Action::read_file_data(false, true, 0, 0, 8))
+        .build();
+    assert_eq!(
+        &cmd.encode()[..],
+        &hex!("72 D7 02 00 00 20 01 0011223344556677  41 00 00 08")
+    );
+
+    let cmd = CommandBuilder::new()
+        .forward_to_vid(hex!("ABCD"), 0xFF)
+        .with_qos(dash7::Qos {
+            retry: dash7::RetryMode::No,
+            resp: dash7::RespMode::No,
+        })
+        .build();
+    assert_eq!(&cmd.encode()[..], &hex!("72 D7 00 00 00 30 FF ABCD"));
+}
+
+#[test]
+#[should_panic]
+fn test_with_qos_without_forward() {
+    CommandBuilder::new()
+        .action(Action::nop(false, false))
+        .with_qos(dash7::Qos {
+            retry: dash7::RetryMode::No,
+            resp: dash7::RespMode::No,
+        });
+}
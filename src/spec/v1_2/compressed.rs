@@ -0,0 +1,168 @@
+//! Dash7 compressed format: a byte `EEEMMMMM` representing the value `4^E * M`.
+//!
+//! It is used to encode wide ranges of values (times, counts) on a single byte, losing precision
+//! on the big values.
+
+/// Biggest value representable in the compressed format (`4^7 * 31`).
+pub const MAX: u32 = 507_904;
+
+/// Value represented by a compressed byte
+pub fn decode(n: u8) -> u32 {
+    let exponent = (n >> 5) as u32;
+    let mantissa = (n & 0x1F) as u32;
+    4u32.pow(exponent) * mantissa
+}
+
+/// Compressed byte representing the closest value to `value`.
+///
+/// Values above [`MAX`] saturate to it. On ties, the smallest value is chosen.
+pub fn encode_nearest(value: u32) -> u8 {
+    let mut best = (u32::MAX, 0u8);
+    for exponent in 0..8u8 {
+        let scale = 4u32.pow(exponent as u32);
+        for mantissa in [value / scale, (value / scale).saturating_add(1)] {
+            let mantissa = mantissa.min(0x1F);
+            let candidate = mantissa * scale;
+            let distance = candidate.abs_diff(value);
+            if distance < best.0 || (distance == best.0 && candidate < decode(best.1)) {
+                best = (distance, (exponent << 5) | mantissa as u8);
+            }
+        }
+    }
+    best.1
+}
+
+/// Duration in the compressed format, counted in units of `UNIT_MS` milliseconds.
+///
+/// The raw byte is public, as encoded on the air. Use [`CompressedSeconds`] or
+/// [`CompressedMillis`] rather than picking a unit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct CompressedTime<const UNIT_MS: u64>(pub u8);
+/// Compressed duration in seconds
+pub type CompressedSeconds = CompressedTime<1000>;
+/// Compressed duration in milliseconds
+pub type CompressedMillis = CompressedTime<1>;
+impl<const UNIT_MS: u64> CompressedTime<UNIT_MS> {
+    /// Compressed time closest to `duration`, saturating at [`MAX`] units.
+    pub fn from_duration(duration: std::time::Duration) -> Self {
+        let unit = std::time::Duration::from_millis(UNIT_MS).as_nanos();
+        let units = (duration.as_nanos() + unit / 2) / unit;
+        Self(encode_nearest(units.min(MAX as u128) as u32))
+    }
+
+    pub fn to_duration(self) -> std::time::Duration {
+        std::time::Duration::from_millis(decode(self.0) as u64 * UNIT_MS)
+    }
+
+    /// Number of units represented
+    pub fn units(self) -> u32 {
+        decode(self.0)
+    }
+}
+impl<const UNIT_MS: u64> From<u8> for CompressedTime<UNIT_MS> {
+    fn from(n: u8) -> Self {
+        Self(n)
+    }
+}
+impl<const UNIT_MS: u64> From<CompressedTime<UNIT_MS>> for u8 {
+    fn from(time: CompressedTime<UNIT_MS>) -> Self {
+        time.0
+    }
+}
+impl std::fmt::Display for CompressedSeconds {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}s", self.units())
+    }
+}
+impl std::fmt::Display for CompressedMillis {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}ms", self.units())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(decode(0x00), 0);
+        assert_eq!(decode(0x01), 1);
+        assert_eq!(decode(0x1F), 31);
+        assert_eq!(decode(0x21), 4);
+        assert_eq!(decode(0x3F), 124);
+        assert_eq!(decode(0x41), 16);
+        assert_eq!(decode(0xFF), MAX);
+    }
+
+    #[test]
+    fn test_encode_nearest() {
+        assert_eq!(encode_nearest(0), 0x00);
+        assert_eq!(encode_nearest(31), 0x1F);
+        // 32 needs an exponent of 1: 4 * 8
+        assert_eq!(encode_nearest(32), 0x28);
+        // 33 is closer to 32 than 36
+        assert_eq!(encode_nearest(33), 0x28);
+        assert_eq!(encode_nearest(35), 0x29);
+        assert_eq!(encode_nearest(MAX), 0xFF);
+        assert_eq!(encode_nearest(u32::MAX), 0xFF);
+    }
+
+    #[test]
+    fn test_compressed_seconds() {
+        use std::time::Duration;
+        assert_eq!(
+            CompressedSeconds::from(0x23).to_duration(),
+            Duration::from_secs(12)
+        );
+        assert_eq!(CompressedSeconds::from(0x23).to_string(), "12s");
+        assert_eq!(
+            CompressedSeconds::from(0xFF).to_duration(),
+            Duration::from_secs(MAX as u64)
+        );
+        assert_eq!(
+            CompressedSeconds::from_duration(Duration::from_secs(32)),
+            CompressedTime(0x28)
+        );
+        // Rounded to the closest second, then to the closest representable value
+        assert_eq!(
+            CompressedSeconds::from_duration(Duration::from_millis(30_600)),
+            CompressedTime(0x1F)
+        );
+        assert_eq!(
+            CompressedSeconds::from_duration(Duration::from_secs(u32::MAX as u64 * 10)),
+            CompressedTime(0xFF)
+        );
+    }
+
+    #[test]
+    fn test_compressed_millis() {
+        use std::time::Duration;
+        assert_eq!(
+            CompressedMillis::from(0x34).to_duration(),
+            Duration::from_millis(80)
+        );
+        assert_eq!(CompressedMillis::from(0x34).to_string(), "80ms");
+        assert_eq!(
+            CompressedMillis::from_duration(Duration::from_millis(80)),
+            CompressedTime(0x34)
+        );
+        assert_eq!(
+            CompressedMillis::from_duration(Duration::from_secs(1)),
+            CompressedTime(0x70)
+        );
+        assert_eq!(
+            CompressedMillis::from(0x70).to_duration(),
+            Duration::from_millis(1024)
+        );
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        for n in 0..=0xFFu8 {
+            assert_eq!(decode(encode_nearest(decode(n))), decode(n));
+        }
+    }
+}
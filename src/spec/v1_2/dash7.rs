@@ -1,4 +1,5 @@
 use crate::codec::{Codec, StdError, WithOffset, WithSize};
+pub use crate::spec::v1_2::compressed::{CompressedMillis, CompressedSeconds, CompressedTime};
 #[cfg(test)]
 use crate::test_tools::test_item;
 #[cfg(test)]
@@ -7,6 +8,7 @@ use std::convert::TryFrom;
 
 /// Encryption algorigthm for over-the-air packets
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum NlsMethod {
     None = 0,
@@ -32,6 +34,44 @@ impl NlsMethod {
             _ => panic!(),
         }
     }
+
+    /// Length of the message integrity code appended to the secured packets, in bytes
+    pub fn mic_len(self) -> usize {
+        match self {
+            Self::None | Self::AesCtr => 0,
+            Self::AesCbcMac32 | Self::AesCcm32 => 4,
+            Self::AesCbcMac64 | Self::AesCcm64 => 8,
+            Self::AesCbcMac128 | Self::AesCcm128 => 16,
+        }
+    }
+
+    /// Length of the key used by the method, in bytes (all the methods use AES-128)
+    pub fn key_len(self) -> usize {
+        match self {
+            Self::None => 0,
+            _ => 16,
+        }
+    }
+
+    /// Whether the payload is encrypted
+    pub fn requires_encryption(self) -> bool {
+        matches!(
+            self,
+            Self::AesCtr | Self::AesCcm128 | Self::AesCcm64 | Self::AesCcm32
+        )
+    }
+
+    /// Whether the packets are authenticated (carry a MIC)
+    pub fn requires_auth(self) -> bool {
+        self.mic_len() != 0
+    }
+
+    /// Whether the method protects the packets at least as well as `required`: encrypted if
+    /// `required` is, and with a MIC at least as long as the one of `required`.
+    pub fn satisfies(self, required: NlsMethod) -> bool {
+        (self.requires_encryption() || !required.requires_encryption())
+            && self.mic_len() >= required.mic_len()
+    }
 }
 
 impl std::fmt::Display for NlsMethod {
@@ -44,7 +84,20 @@ impl std::fmt::Display for NlsMethod {
     }
 }
 
+/// Security state of a secured link: the key counter followed by the 4 bytes frame counter.
+///
+/// ```text
+/// | byte 0      | bytes 1..5                 |
+/// | key counter | frame counter (big endian) |
+/// ```
+///
+/// The key counter identifies the key the frame was secured with, and is incremented on each
+/// key renewal. The frame counter is incremented on each secured frame.
+///
+/// Its size does not depend on the security method: the method only changes the MIC carried by
+/// the packets (see [`NlsMethod::mic_len`]), which is not part of the state.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum NlsState {
     None,
     AesCtr([u8; 5]),
@@ -102,6 +155,67 @@ impl NlsState {
             Self::AesCcm32(state) => Some(state),
         }
     }
+
+    /// Counter of the key used to secure the frame, if the link is secured
+    pub fn key_counter(&self) -> Option<u8> {
+        self.get_data().map(|state| state[0])
+    }
+
+    /// Counter of the secured frame, if the link is secured
+    pub fn frame_counter(&self) -> Option<u32> {
+        self.get_data()
+            .map(|state| u32::from_be_bytes([state[1], state[2], state[3], state[4]]))
+    }
+}
+
+#[test]
+fn test_nls_state_counters() {
+    let state = NlsState::AesCcm32(hex!("03 00 01 02 FF"));
+    assert_eq!(state.key_counter(), Some(3));
+    assert_eq!(state.frame_counter(), Some(0x000102FF));
+    assert_eq!(NlsState::None.key_counter(), None);
+    assert_eq!(NlsState::None.frame_counter(), None);
+}
+
+#[test]
+fn test_nls_method_capabilities() {
+    let methods = (0..8).map(|n| unsafe { NlsMethod::from(n) });
+    let capabilities: Vec<_> = methods
+        .map(|m| {
+            (
+                m.mic_len(),
+                m.key_len(),
+                m.requires_encryption(),
+                m.requires_auth(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        capabilities,
+        vec![
+            (0, 0, false, false),
+            (0, 16, true, false),
+            (16, 16, false, true),
+            (8, 16, false, true),
+            (4, 16, false, true),
+            (16, 16, true, true),
+            (8, 16, true, true),
+            (4, 16, true, true),
+        ]
+    );
+
+    assert!(NlsMethod::AesCcm64.satisfies(NlsMethod::AesCcm32));
+    assert!(NlsMethod::AesCcm64.satisfies(NlsMethod::AesCbcMac64));
+    assert!(NlsMethod::AesCcm32.satisfies(NlsMethod::AesCtr));
+    assert!(!NlsMethod::AesCcm32.satisfies(NlsMethod::AesCcm64));
+    assert!(!NlsMethod::AesCbcMac128.satisfies(NlsMethod::AesCtr));
+    assert!(!NlsMethod::AesCtr.satisfies(NlsMethod::AesCbcMac32));
+    for n in 0..8 {
+        let method = unsafe { NlsMethod::from(n) };
+        assert!(method.satisfies(method));
+        assert!(method.satisfies(NlsMethod::None));
+        assert_eq!(NlsMethod::None.satisfies(method), method == NlsMethod::None);
+    }
 }
 
 impl std::fmt::Display for NlsState {
@@ -126,7 +240,7 @@ impl std::fmt::Display for NlsState {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 #[repr(u8)]
 pub enum AddressType {
     NbId = 0,
@@ -147,6 +261,35 @@ impl From<u8> for AddressType {
     }
 }
 
+impl AddressType {
+    /// Length of the identifier of an address of this type, in bytes
+    pub fn id_len(self) -> usize {
+        match self {
+            Self::NbId => 1,
+            Self::NoId => 0,
+            Self::Uid => 8,
+            Self::Vid => 2,
+        }
+    }
+}
+
+/// Identifier given to [`Address::new`] not matching the length of its address type
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AddressLengthError {
+    pub id_type: AddressType,
+    pub expected: usize,
+    pub actual: usize,
+}
+impl std::fmt::Display for AddressLengthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{} identifier must be {} bytes long, got {}",
+            self.id_type, self.expected, self.actual
+        )
+    }
+}
+
 impl std::fmt::Display for AddressType {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(
@@ -164,6 +307,7 @@ impl std::fmt::Display for AddressType {
 
 /// Dash7 device address
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Address {
     /// Broadcast to an estimated number of receivers, encoded in compressed format on a byte.
     NbId(u8),
@@ -175,6 +319,21 @@ pub enum Address {
     Vid([u8; 2]),
 }
 impl Address {
+    /// Builds an address from its type and identifier, which must be exactly
+    /// [`id_len`](AddressType::id_len) bytes long.
+    pub fn new(id_type: AddressType, id: &[u8]) -> Result<Self, AddressLengthError> {
+        if id.len() != id_type.id_len() {
+            return Err(AddressLengthError {
+                id_type,
+                expected: id_type.id_len(),
+                actual: id.len(),
+            });
+        }
+        Ok(Self::parse(id_type, id)
+            .expect("identifier length already checked")
+            .value)
+    }
+
     pub fn id_type(&self) -> AddressType {
         match self {
             Self::NoId => AddressType::NoId,
@@ -183,6 +342,63 @@ impl Address {
             Self::Vid(_) => AddressType::Vid,
         }
     }
+
+    /// Estimated number of receivers of a `NbId` address, decoded from its compressed format.
+    pub fn nbid_count(&self) -> Option<u32> {
+        match self {
+            Self::NbId(n) => Some(crate::spec::v1_2::compressed::decode(*n)),
+            _ => None,
+        }
+    }
+
+    /// `NbId` address targeting approximately `count` receivers.
+    ///
+    /// The count is rounded to the nearest value representable in the compressed format.
+    pub fn nbid_from_count(count: u32) -> Self {
+        Self::NbId(crate::spec::v1_2::compressed::encode_nearest(count))
+    }
+}
+#[test]
+fn test_address_new() {
+    assert_eq!(
+        Address::new(AddressType::Uid, &hex!("0011223344556677")),
+        Ok(Address::Uid(hex!("0011223344556677")))
+    );
+    assert_eq!(
+        Address::new(AddressType::Vid, &hex!("ABCD")),
+        Ok(Address::Vid(hex!("ABCD")))
+    );
+    assert_eq!(
+        Address::new(AddressType::NbId, &[0x39]),
+        Ok(Address::NbId(0x39))
+    );
+    assert_eq!(Address::new(AddressType::NoId, &[]), Ok(Address::NoId));
+    let err = Address::new(AddressType::Uid, &hex!("00112233445566")).unwrap_err();
+    assert_eq!(
+        err,
+        AddressLengthError {
+            id_type: AddressType::Uid,
+            expected: 8,
+            actual: 7,
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "UID identifier must be 8 bytes long, got 7"
+    );
+    assert!(Address::new(AddressType::Vid, &hex!("ABCDEF")).is_err());
+    assert!(Address::new(AddressType::NoId, &[0]).is_err());
+}
+#[test]
+fn test_address_nbid_count() {
+    assert_eq!(Address::NbId(0x00).nbid_count(), Some(0));
+    assert_eq!(Address::NbId(0x1F).nbid_count(), Some(31));
+    assert_eq!(Address::NbId(0x28).nbid_count(), Some(32));
+    assert_eq!(Address::NbId(0xFF).nbid_count(), Some(507_904));
+    assert_eq!(Address::NoId.nbid_count(), None);
+    assert_eq!(Address::nbid_from_count(10), Address::NbId(0x0A));
+    assert_eq!(Address::nbid_from_count(100), Address::NbId(0x39));
+    assert_eq!(Address::nbid_from_count(1000), Address::NbId(0x70));
 }
 impl std::fmt::Display for Address {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -196,12 +412,7 @@ impl std::fmt::Display for Address {
 }
 impl Address {
     pub(crate) fn encoded_size(&self) -> usize {
-        match self {
-            Address::NbId(_) => 1,
-            Address::NoId => 0,
-            Address::Uid(_) => 8,
-            Address::Vid(_) => 2,
-        }
+        self.id_type().id_len()
     }
 
     pub(crate) unsafe fn encode_in(&self, out: &mut [u8]) -> usize {
@@ -243,7 +454,7 @@ impl Address {
                 let mut uid = [0u8; 8];
                 uid.copy_from_slice(
                     data.get(..8).ok_or_else(|| {
-                        WithOffset::new_head(StdError::MissingBytes(data.len() - 8))
+                        WithOffset::new_head(StdError::MissingBytes(8 - data.len()))
                     })?,
                 );
                 WithSize {
@@ -255,7 +466,7 @@ impl Address {
                 let mut vid = [0u8; 2];
                 vid.copy_from_slice(
                     data.get(..2).ok_or_else(|| {
-                        WithOffset::new_head(StdError::MissingBytes(data.len() - 2))
+                        WithOffset::new_head(StdError::MissingBytes(2 - data.len()))
                     })?,
                 );
                 WithSize {
@@ -266,8 +477,30 @@ impl Address {
         })
     }
 }
+#[test]
+fn test_address_parse_truncated() {
+    let data = [0xAB; 8];
+    for (ty, size) in [
+        (AddressType::NoId, 0),
+        (AddressType::NbId, 1),
+        (AddressType::Uid, 8),
+        (AddressType::Vid, 2),
+    ] {
+        for len in 0..size {
+            assert_eq!(
+                Address::parse(ty, &data[..len]),
+                Err(WithOffset::new_head(StdError::MissingBytes(size - len))),
+                "{:?} from {} bytes",
+                ty,
+                len
+            );
+        }
+        assert_eq!(Address::parse(ty, &data[..size]).unwrap().size, size);
+    }
+}
 
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 // ALP_SPEC: Aren't there supposed to be more retry modes?
 /// The Retry Modes define the pattern for re-flushing a FIFO that terminates on error.
 ///
@@ -305,6 +538,7 @@ impl std::fmt::Display for RetryMode {
 
 /// The Response Modes define the condition for termination on success of a Request
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RespMode {
     /// A Request is acknowledged if the DLL CSMA-CA routine succeeds. No
     /// responses are expected.
@@ -384,6 +618,7 @@ impl std::fmt::Display for RespMode {
 
 /// Qos of the request
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Qos {
     pub retry: RetryMode,
     pub resp: RespMode,
@@ -394,6 +629,16 @@ pub enum QosDecodingError {
     UnknownRetryMode(u8),
     UnknownRespMode(u8),
 }
+impl QosDecodingError {
+    /// Minimum number of bytes missing to decode the item, if the error is caused by a
+    /// truncated input.
+    pub fn missing_bytes(&self) -> Option<usize> {
+        match self {
+            Self::MissingBytes(n) => Some(*n as usize),
+            Self::UnknownRetryMode(_) | Self::UnknownRespMode(_) => None,
+        }
+    }
+}
 impl Codec for Qos {
     type Error = QosDecodingError;
     fn encoded_size(&self) -> usize {
@@ -434,6 +679,7 @@ impl std::fmt::Display for Qos {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum GroupCondition {
     /// <, =, > (always true)
@@ -446,6 +692,18 @@ pub enum GroupCondition {
     GreaterThan = 3,
 }
 
+impl GroupCondition {
+    /// Group condition held by the 2 lowest bits of `n`, the other bits being ignored.
+    pub(crate) fn from_bits(n: u8) -> Self {
+        match n & 0x03 {
+            0 => GroupCondition::Any,
+            1 => GroupCondition::NotEqual,
+            2 => GroupCondition::Equal,
+            _ => GroupCondition::GreaterThan,
+        }
+    }
+}
+
 impl TryFrom<u8> for GroupCondition {
     type Error = u8;
     fn try_from(value: u8) -> Result<Self, Self::Error> {
@@ -465,20 +723,98 @@ impl std::fmt::Display for GroupCondition {
             f,
             "GCD={}",
             match self {
-                Self::Any => "X",
-                Self::NotEqual => "!=",
-                Self::Equal => "==",
-                Self::GreaterThan => ">",
+                Self::Any => "ANY",
+                Self::NotEqual => "NEQ",
+                Self::Equal => "EQ",
+                Self::GreaterThan => "GT",
             }
         )
     }
 }
 
+impl GroupCondition {
+    /// Tells whether a device satisfies the condition, given how its value compares
+    /// to the reference value.
+    pub fn evaluate(&self, ordering: std::cmp::Ordering) -> bool {
+        use std::cmp::Ordering;
+        match self {
+            Self::Any => true,
+            Self::NotEqual => ordering != Ordering::Equal,
+            Self::Equal => ordering == Ordering::Equal,
+            Self::GreaterThan => ordering == Ordering::Greater,
+        }
+    }
+}
+
+#[test]
+fn test_group_condition() {
+    use std::cmp::Ordering;
+    let orderings = [Ordering::Less, Ordering::Equal, Ordering::Greater];
+    for (condition, expected) in [
+        (GroupCondition::Any, [true, true, true]),
+        (GroupCondition::NotEqual, [true, false, true]),
+        (GroupCondition::Equal, [false, true, false]),
+        (GroupCondition::GreaterThan, [false, false, true]),
+    ] {
+        for (ordering, expected) in orderings.iter().zip(expected.iter()) {
+            assert_eq!(condition.evaluate(*ordering), *expected);
+        }
+        assert_eq!(GroupCondition::try_from(condition as u8), Ok(condition));
+        assert_eq!(GroupCondition::from_bits(condition as u8 | 0xFC), condition);
+    }
+    assert_eq!(GroupCondition::try_from(4), Err(4));
+    assert_eq!(GroupCondition::Any.to_string(), "GCD=ANY");
+    assert_eq!(GroupCondition::NotEqual.to_string(), "GCD=NEQ");
+    assert_eq!(GroupCondition::Equal.to_string(), "GCD=EQ");
+    assert_eq!(GroupCondition::GreaterThan.to_string(), "GCD=GT");
+}
+
+/// Access class of a device: it selects the access profile and the subbands used to
+/// communicate with it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AccessClass(pub u8);
+impl AccessClass {
+    /// Index of the access profile (high nibble)
+    pub fn specifier(self) -> u8 {
+        self.0 >> 4
+    }
+    /// Subband mask of the access profile (low nibble)
+    pub fn subband(self) -> u8 {
+        self.0 & 0x0F
+    }
+}
+impl From<u8> for AccessClass {
+    fn from(n: u8) -> Self {
+        Self(n)
+    }
+}
+impl From<AccessClass> for u8 {
+    fn from(access_class: AccessClass) -> Self {
+        access_class.0
+    }
+}
+impl std::fmt::Display for AccessClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "0x{:02X}", self.0)
+    }
+}
+#[test]
+fn test_access_class() {
+    let access_class = AccessClass::from(0x21);
+    assert_eq!(access_class.specifier(), 2);
+    assert_eq!(access_class.subband(), 1);
+    assert_eq!(u8::from(access_class), 0x21);
+    assert_eq!(access_class.to_string(), "0x21");
+    assert_eq!(AccessClass(0x0F).to_string(), "0x0F");
+}
+
 /// Section 9.2.1
 ///
 /// Parameters to handle the sending of a request.
 // ALP SPEC: Add link to D7a section
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InterfaceConfiguration {
     pub qos: Qos,
     /// Flush Start Timeout in Compressed Format, unit is in seconds
@@ -491,13 +827,13 @@ pub struct InterfaceConfiguration {
     /// then we can aggregate the requests, to avoid advertising twice. Another example would be if
     /// the target sends us a packet, the modem can aggregate our request to the response of the
     /// request of the target.
-    pub to: u8,
+    pub to: CompressedSeconds,
     /// Response Execution Delay in Compressed Format, unit is in milliseconds.
     ///
     /// Time given to the target to process the request.
-    pub te: u8,
+    pub te: CompressedMillis,
     /// Access class of the targeted listening device
-    pub access_class: u8,
+    pub access_class: AccessClass,
     /// Security method
     pub nls_method: NlsMethod,
     /// Address of the target.
@@ -510,19 +846,37 @@ pub struct InterfaceConfiguration {
     pub group_condition: GroupCondition,
 }
 
+/// Formats the interface configuration fields shared by every dialect, so that their outputs
+/// only differ by the dialect specific fields appended after it.
+pub(crate) fn fmt_interface_configuration_common(
+    f: &mut std::fmt::Formatter,
+    qos: &dyn std::fmt::Display,
+    to: CompressedSeconds,
+    access_class: AccessClass,
+    nls_method: NlsMethod,
+    address: &dyn std::fmt::Display,
+) -> std::fmt::Result {
+    write!(
+        f,
+        "{},to={}|{},{},{}",
+        qos, to, access_class, nls_method, address
+    )
+}
+
 impl std::fmt::Display for InterfaceConfiguration {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(
+        fmt_interface_configuration_common(
             f,
-            "{},{},{}|0x{},use_vid={},{},{},{}",
-            self.qos,
+            &self.qos,
             self.to,
-            self.te,
-            hex::encode_upper([self.access_class]),
-            self.use_vid,
+            self.access_class,
             self.nls_method,
-            self.group_condition,
-            self.address
+            &self.address,
+        )?;
+        write!(
+            f,
+            ",te={},use_vid={},{}",
+            self.te, self.use_vid, self.group_condition
         )
     }
 }
@@ -532,6 +886,16 @@ pub enum InterfaceConfigurationDecodingError {
     MissingBytes(usize),
     Qos(QosDecodingError),
 }
+impl InterfaceConfigurationDecodingError {
+    /// Minimum number of bytes missing to decode the item, if the error is caused by a
+    /// truncated input.
+    pub fn missing_bytes(&self) -> Option<usize> {
+        match self {
+            Self::MissingBytes(n) => Some(*n),
+            Self::Qos(e) => e.missing_bytes(),
+        }
+    }
+}
 
 impl From<StdError> for InterfaceConfigurationDecodingError {
     fn from(e: StdError) -> Self {
@@ -548,13 +912,13 @@ impl Codec for InterfaceConfiguration {
     }
     unsafe fn encode_in(&self, out: &mut [u8]) -> usize {
         self.qos.encode_in(out);
-        out[1] = self.to;
-        out[2] = self.te;
+        out[1] = self.to.0;
+        out[2] = self.te.0;
         out[3] = ((self.group_condition as u8) << 6)
             | ((self.address.id_type() as u8) << 4)
             | ((self.use_vid as u8) << 3)
             | (self.nls_method as u8);
-        out[4] = self.access_class;
+        out[4] = self.access_class.0;
         5 + self.address.encode_in(&mut out[5..])
     }
     fn decode(out: &[u8]) -> Result<WithSize<Self>, WithOffset<Self::Error>> {
@@ -567,13 +931,13 @@ impl Codec for InterfaceConfiguration {
             value: qos,
             size: qos_size,
         } = Qos::decode(out).map_err(|e| e.map_value(Self::Error::Qos))?;
-        let to = out[1];
-        let te = out[2];
-        let group_condition = GroupCondition::try_from((out[3] >> 6) & 0x03).unwrap();
+        let to = CompressedTime(out[1]);
+        let te = CompressedTime(out[2]);
+        let group_condition = GroupCondition::from_bits(out[3] >> 6);
         let address_type = AddressType::from((out[3] & 0x30) >> 4);
         let use_vid = (out[3] & 0x08) != 0;
         let nls_method = unsafe { NlsMethod::from(out[3] & 0x07) };
-        let access_class = out[4];
+        let access_class = AccessClass(out[4]);
         let WithSize {
             value: address,
             size: address_size,
@@ -607,10 +971,10 @@ fn test_interface_configuration() {
                 retry: RetryMode::No,
                 resp: RespMode::Any,
             },
-            to: 0x23,
-            te: 0x34,
+            to: CompressedTime(0x23),
+            te: CompressedTime(0x34),
             nls_method: NlsMethod::AesCcm32,
-            access_class: 0xFF,
+            access_class: AccessClass(0xFF),
             use_vid: false,
             address: Address::Vid([0xAB, 0xCD]),
             group_condition: GroupCondition::Any,
@@ -619,6 +983,26 @@ fn test_interface_configuration() {
     )
 }
 
+// The D7ASP interface configuration has no background frame parameter: background frames are
+// only sent by the advertising protocol of the stack, which is set up by the access profiles
+// rather than by ALP. There is also no free bit to hold such a flag: every bit of the addressee
+// control byte is assigned, and the 2 upper bits of the QoS byte are not modelled and dropped.
+#[test]
+fn test_interface_configuration_has_no_background_flag() {
+    for control in 0..=0xFFu8 {
+        let data = [
+            0x02, 0x23, 0x34, control, 0xFF, 0xAB, 0xCD, 0, 0, 0, 0, 0, 0,
+        ];
+        let WithSize { value, size } = InterfaceConfiguration::decode(&data).unwrap();
+        assert_eq!(value.encode()[..], data[..size]);
+    }
+
+    let conf = InterfaceConfiguration::decode(&hex!("C2 23 34   37 FF ABCD"))
+        .unwrap()
+        .value;
+    assert_eq!(&conf.encode()[..], &hex!("02 23 34   37 FF ABCD"));
+}
+
 #[test]
 fn test_interface_configuration_with_address_nbid() {
     test_item(
@@ -627,10 +1011,10 @@ fn test_interface_configuration_with_address_nbid() {
                 retry: RetryMode::No,
                 resp: RespMode::Any,
             },
-            to: 0x23,
-            te: 0x34,
+            to: CompressedTime(0x23),
+            te: CompressedTime(0x34),
             nls_method: NlsMethod::None,
-            access_class: 0x00,
+            access_class: AccessClass(0x00),
             address: Address::NbId(0x15),
             use_vid: true,
             group_condition: GroupCondition::NotEqual,
@@ -646,10 +1030,10 @@ fn test_interface_configuration_with_address_noid() {
                 retry: RetryMode::No,
                 resp: RespMode::Any,
             },
-            to: 0x23,
-            te: 0x34,
+            to: CompressedTime(0x23),
+            te: CompressedTime(0x34),
             nls_method: NlsMethod::AesCbcMac128,
-            access_class: 0x24,
+            access_class: AccessClass(0x24),
             address: Address::NoId,
             use_vid: false,
             group_condition: GroupCondition::Equal,
@@ -665,10 +1049,10 @@ fn test_interface_configuration_with_address_uid() {
                 retry: RetryMode::No,
                 resp: RespMode::Any,
             },
-            to: 0x23,
-            te: 0x34,
+            to: CompressedTime(0x23),
+            te: CompressedTime(0x34),
             nls_method: NlsMethod::AesCcm64,
-            access_class: 0x48,
+            access_class: AccessClass(0x48),
             address: Address::Uid([0, 1, 2, 3, 4, 5, 6, 7]),
             use_vid: true,
             group_condition: GroupCondition::GreaterThan,
@@ -684,10 +1068,10 @@ fn test_interface_configuration_with_address_vid() {
                 retry: RetryMode::No,
                 resp: RespMode::Any,
             },
-            to: 0x23,
-            te: 0x34,
+            to: CompressedTime(0x23),
+            te: CompressedTime(0x34),
             nls_method: NlsMethod::AesCcm32,
-            access_class: 0xFF,
+            access_class: AccessClass(0xFF),
             address: Address::Vid([0xAB, 0xCD]),
             use_vid: false,
             group_condition: GroupCondition::Any,
@@ -696,9 +1080,24 @@ fn test_interface_configuration_with_address_vid() {
     )
 }
 
+/// Identifier of a D7ATP transaction: the dialog id (`token`) and the transaction id (`seq`)
+/// of an [InterfaceStatus].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DialogId {
+    pub token: u8,
+    pub seq: u8,
+}
+impl std::fmt::Display for DialogId {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "dlg({}:{})", self.token, self.seq)
+    }
+}
+
 /// Dash7 metadata upon packet reception.
 // ALP SPEC: Add link to D7a section (names do not even match)
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InterfaceStatus {
     /// PHY layer channel header
     pub ch_header: u8,
@@ -721,7 +1120,7 @@ pub struct InterfaceStatus {
     /// Frequency offset in Hz
     pub fof: u16,
     /// Listening access class of the sender
-    pub access_class: u8,
+    pub access_class: AccessClass,
     /// Address of source
     pub address: Address,
     /// Security data
@@ -731,7 +1130,7 @@ impl std::fmt::Display for InterfaceStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(
             f,
-            "ch({};{}),sig({},{},{}),s={},tok={},sq={},rto={},fof={},xclass=0x{},{},{}",
+            "ch({};{}),sig({},{},{}),s={},tok={},sq={},rto={},fof={},xclass={},{},{}",
             self.ch_header,
             self.ch_idx,
             self.rxlev,
@@ -742,7 +1141,7 @@ impl std::fmt::Display for InterfaceStatus {
             self.seq,
             self.resp_to,
             self.fof,
-            hex::encode_upper([self.access_class]),
+            self.access_class,
             self.address,
             self.nls_state
         )
@@ -782,7 +1181,7 @@ impl Codec for InterfaceStatus {
         i += 1;
         out[i] = ((self.address.id_type() as u8) << 4) | (self.nls_state.method() as u8);
         i += 1;
-        out[i] = self.access_class;
+        out[i] = self.access_class.0;
         i += 1;
         i += self.address.encode_in(&mut out[i..]);
         if let Some(data) = self.nls_state.get_data() {
@@ -792,9 +1191,9 @@ impl Codec for InterfaceStatus {
         i
     }
     fn decode(out: &[u8]) -> Result<WithSize<Self>, WithOffset<Self::Error>> {
-        if out.len() < 10 {
+        if out.len() < 15 {
             return Err(WithOffset::new_head(Self::Error::MissingBytes(
-                10 - out.len(),
+                15 - out.len(),
             )));
         }
         let ch_header = out[0];
@@ -810,7 +1209,7 @@ impl Codec for InterfaceStatus {
 
         let address_type = AddressType::from((out[13] & 0x30) >> 4);
         let nls_method = unsafe { NlsMethod::from(out[13] & 0x07) };
-        let access_class = out[14];
+        let access_class = AccessClass(out[14]);
 
         let WithSize {
             size: address_size,
@@ -869,7 +1268,7 @@ fn test_interface_status() {
             seq: 7,
             resp_to: 8,
             fof: 9,
-            access_class: 0xFF,
+            access_class: AccessClass(0xFF),
             address: Address::Vid([0xAB, 0xCD]),
             nls_state: NlsState::AesCcm32(hex!("00 11 22 33 44")),
         },
@@ -877,6 +1276,274 @@ fn test_interface_status() {
     )
 }
 
+#[test]
+fn test_interface_status_truncated() {
+    let status = InterfaceStatus {
+        ch_header: 1,
+        ch_idx: 0x0123,
+        rxlev: 2,
+        lb: 3,
+        snr: 4,
+        status: 5,
+        token: 6,
+        seq: 7,
+        resp_to: 8,
+        fof: 9,
+        access_class: AccessClass(0xFF),
+        address: Address::Vid([0xAB, 0xCD]),
+        nls_state: NlsState::AesCcm32(hex!("00 11 22 33 44")),
+    };
+    let data = status.encode();
+    // Every truncation, including inside the NLS state, is reported instead of read past
+    for size in 0..data.len() {
+        match InterfaceStatus::decode(&data[..size]) {
+            Err(WithOffset {
+                value: StdError::MissingBytes(n),
+                ..
+            }) => assert!(n > 0, "{}", size),
+            other => panic!("{}: {:?}", size, other),
+        }
+    }
+    assert_eq!(
+        InterfaceStatus::decode(&data[..data.len() - 2]),
+        Err(WithOffset::new(data.len() - 5, StdError::MissingBytes(2)))
+    );
+}
+
+/// Signal field of an [`InterfaceStatus`] out of its encodable range
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InterfaceStatusBuildError {
+    /// RX level (in dBm) outside of `[-255, 0]`
+    RxLevOutOfRange(i16),
+    /// Link budget (in dB) outside of `[0, 255]`
+    LinkBudgetOutOfRange(i16),
+    /// Signal-to-noise ratio (in dB) outside of `[0, 255]`
+    SnrOutOfRange(i16),
+}
+impl std::fmt::Display for InterfaceStatusBuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::RxLevOutOfRange(v) => write!(f, "rx level {} dBm not in [-255, 0]", v),
+            Self::LinkBudgetOutOfRange(v) => write!(f, "link budget {} dB not in [0, 255]", v),
+            Self::SnrOutOfRange(v) => write!(f, "snr {} dB not in [0, 255]", v),
+        }
+    }
+}
+
+/// Helper to build an [`InterfaceStatus`], defaulting every field but the address to 0 (or no
+/// security).
+///
+/// The signal fields are given as signed values (`rxlev` in dBm, `lb` and `snr` in dB) and
+/// checked against their encodable range by [`build`](Self::build).
+#[derive(Clone, Debug, PartialEq)]
+pub struct InterfaceStatusBuilder {
+    ch_header: u8,
+    ch_idx: u16,
+    rxlev: i16,
+    lb: i16,
+    snr: i16,
+    status: u8,
+    token: u8,
+    seq: u8,
+    resp_to: u16,
+    fof: u16,
+    access_class: AccessClass,
+    address: Address,
+    nls_state: NlsState,
+}
+impl InterfaceStatus {
+    pub fn builder(address: Address) -> InterfaceStatusBuilder {
+        InterfaceStatusBuilder {
+            ch_header: 0,
+            ch_idx: 0,
+            rxlev: 0,
+            lb: 0,
+            snr: 0,
+            status: 0,
+            token: 0,
+            seq: 0,
+            resp_to: 0,
+            fof: 0,
+            access_class: AccessClass(0),
+            address,
+            nls_state: NlsState::None,
+        }
+    }
+
+    /// Received signal strength in dBm (`rxlev` holds its opposite: a `rxlev` of `2` is -2 dBm)
+    pub fn rssi_dbm(&self) -> i16 {
+        -(self.rxlev as i16)
+    }
+    /// Link budget in dB
+    pub fn link_budget_db(&self) -> i16 {
+        self.lb as i16
+    }
+    /// Signal-to-noise ratio in dB
+    pub fn snr_db(&self) -> i16 {
+        self.snr as i16
+    }
+    /// Dialog and transaction the received packet belongs to
+    pub fn dialog_id(&self) -> DialogId {
+        DialogId {
+            token: self.token,
+            seq: self.seq,
+        }
+    }
+}
+impl InterfaceStatusBuilder {
+    pub fn ch_header(mut self, ch_header: u8) -> Self {
+        self.ch_header = ch_header;
+        self
+    }
+    pub fn ch_idx(mut self, ch_idx: u16) -> Self {
+        self.ch_idx = ch_idx;
+        self
+    }
+    /// RX level in dBm (`-80` for -80 dBm)
+    pub fn rxlev(mut self, dbm: i16) -> Self {
+        self.rxlev = dbm;
+        self
+    }
+    /// Link budget in dB
+    pub fn lb(mut self, db: i16) -> Self {
+        self.lb = db;
+        self
+    }
+    /// Signal-to-noise ratio in dB
+    pub fn snr(mut self, db: i16) -> Self {
+        self.snr = db;
+        self
+    }
+    pub fn status(mut self, status: u8) -> Self {
+        self.status = status;
+        self
+    }
+    pub fn token(mut self, token: u8) -> Self {
+        self.token = token;
+        self
+    }
+    pub fn seq(mut self, seq: u8) -> Self {
+        self.seq = seq;
+        self
+    }
+    pub fn resp_to(mut self, resp_to: u16) -> Self {
+        self.resp_to = resp_to;
+        self
+    }
+    pub fn fof(mut self, fof: u16) -> Self {
+        self.fof = fof;
+        self
+    }
+    pub fn access_class(mut self, access_class: AccessClass) -> Self {
+        self.access_class = access_class;
+        self
+    }
+    pub fn nls_state(mut self, nls_state: NlsState) -> Self {
+        self.nls_state = nls_state;
+        self
+    }
+
+    pub fn build(self) -> Result<InterfaceStatus, InterfaceStatusBuildError> {
+        if !(-255..=0).contains(&self.rxlev) {
+            return Err(InterfaceStatusBuildError::RxLevOutOfRange(self.rxlev));
+        }
+        if !(0..=255).contains(&self.lb) {
+            return Err(InterfaceStatusBuildError::LinkBudgetOutOfRange(self.lb));
+        }
+        if !(0..=255).contains(&self.snr) {
+            return Err(InterfaceStatusBuildError::SnrOutOfRange(self.snr));
+        }
+        Ok(InterfaceStatus {
+            ch_header: self.ch_header,
+            ch_idx: self.ch_idx,
+            rxlev: (-self.rxlev) as u8,
+            lb: self.lb as u8,
+            snr: self.snr as u8,
+            status: self.status,
+            token: self.token,
+            seq: self.seq,
+            resp_to: self.resp_to,
+            fof: self.fof,
+            access_class: self.access_class,
+            address: self.address,
+            nls_state: self.nls_state,
+        })
+    }
+}
+#[test]
+fn test_interface_status_signal() {
+    let status = InterfaceStatus::builder(Address::Vid([0xAB, 0xCD]))
+        .rxlev(-78)
+        .lb(90)
+        .snr(12)
+        .build()
+        .unwrap();
+    assert_eq!(status.rxlev, 78);
+    assert_eq!(status.rssi_dbm(), -78);
+    assert_eq!(status.link_budget_db(), 90);
+    assert_eq!(status.snr_db(), 12);
+}
+#[test]
+fn test_interface_status_dialog_id() {
+    let status = InterfaceStatus::builder(Address::NoId)
+        .token(6)
+        .seq(7)
+        .build()
+        .unwrap();
+    assert_eq!(status.dialog_id(), DialogId { token: 6, seq: 7 });
+    assert_eq!(status.dialog_id().to_string(), "dlg(6:7)");
+}
+#[test]
+fn test_interface_status_builder() {
+    assert_eq!(
+        InterfaceStatus::builder(Address::Vid([0xAB, 0xCD]))
+            .ch_header(1)
+            .ch_idx(0x0123)
+            .rxlev(-2)
+            .lb(3)
+            .snr(4)
+            .status(5)
+            .token(6)
+            .seq(7)
+            .access_class(AccessClass(0xFF))
+            .nls_state(NlsState::AesCcm32(hex!("00 11 22 33 44")))
+            .build(),
+        Ok(InterfaceStatus {
+            ch_header: 1,
+            ch_idx: 0x0123,
+            rxlev: 2,
+            lb: 3,
+            snr: 4,
+            status: 5,
+            token: 6,
+            seq: 7,
+            resp_to: 0,
+            fof: 0,
+            access_class: AccessClass(0xFF),
+            address: Address::Vid([0xAB, 0xCD]),
+            nls_state: NlsState::AesCcm32(hex!("00 11 22 33 44")),
+        })
+    );
+    let builder = InterfaceStatus::builder(Address::NoId);
+    assert_eq!(
+        builder.clone().rxlev(-256).build(),
+        Err(InterfaceStatusBuildError::RxLevOutOfRange(-256))
+    );
+    assert_eq!(
+        builder.clone().rxlev(3).build(),
+        Err(InterfaceStatusBuildError::RxLevOutOfRange(3))
+    );
+    assert_eq!(
+        builder.clone().lb(-1).build(),
+        Err(InterfaceStatusBuildError::LinkBudgetOutOfRange(-1))
+    );
+    assert_eq!(
+        builder.clone().snr(256).build(),
+        Err(InterfaceStatusBuildError::SnrOutOfRange(256))
+    );
+    assert_eq!(builder.rxlev(-255).build().unwrap().rxlev, 255);
+}
+
 pub mod file {
     pub mod id {
         //! File IDs 0x00-0x17 and 0x20-0x2F are reserved by the DASH7 spec.
@@ -6,6 +6,7 @@ use hex_literal::hex;
 
 /// Permissions of a given user regarding a specific file.
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UserPermissions {
     pub read: bool,
     pub write: bool,
@@ -24,6 +25,7 @@ impl std::fmt::Display for UserPermissions {
 }
 /// Description of the permissions for a file for all users.
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Permissions {
     /// Whether data element is encrypted
     /// WARNING: This meaning might be deprecated
@@ -83,6 +85,7 @@ impl Permissions {
 }
 /// File access type event that will trigger an ALP action.
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ActionCondition {
     /// Check for existence
     /// (L)
@@ -118,6 +121,17 @@ impl ActionCondition {
         }
     }
 }
+/// Only the action conditions defined by the specification are accepted: the reserved codes
+/// (4 to 7) are rejected, even though they can be decoded from a file header.
+impl std::convert::TryFrom<u8> for ActionCondition {
+    type Error = u8;
+    fn try_from(n: u8) -> Result<Self, Self::Error> {
+        if n > 3 {
+            return Err(n);
+        }
+        Ok(Self::from(n))
+    }
+}
 impl std::fmt::Display for ActionCondition {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
@@ -131,6 +145,7 @@ impl std::fmt::Display for ActionCondition {
 }
 /// Type of storage
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StorageClass {
     /// The content is not kept in memory. It cannot be read back.
     Transient = 0,
@@ -156,6 +171,15 @@ impl StorageClass {
         }
     }
 }
+impl std::convert::TryFrom<u8> for StorageClass {
+    type Error = u8;
+    fn try_from(n: u8) -> Result<Self, Self::Error> {
+        if n > 3 {
+            return Err(n);
+        }
+        Ok(Self::from(n))
+    }
+}
 impl std::fmt::Display for StorageClass {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(
@@ -171,6 +195,7 @@ impl std::fmt::Display for StorageClass {
     }
 }
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FileProperties {
     /// Enables the D7AActP (ALP action to trigger upon some type of access to this file)
     pub act_en: bool,
@@ -206,6 +231,7 @@ impl FileProperties {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FileHeader {
     /// Permissions of the file
     pub permissions: Permissions,
@@ -241,6 +267,30 @@ impl std::fmt::Display for FileHeader {
         )
     }
 }
+impl FileHeader {
+    /// File holding the ALP command run by the D7AActP, if the action is enabled.
+    ///
+    /// `alp_cmd_fid` is meaningless when `act_en` is not set.
+    pub fn action_command_file(&self) -> Option<u8> {
+        if self.properties.act_en {
+            Some(self.alp_cmd_fid)
+        } else {
+            None
+        }
+    }
+
+    /// File holding the interface on which the D7AActP result is sent, if the action is
+    /// enabled.
+    ///
+    /// `interface_file_id` is meaningless when `act_en` is not set.
+    pub fn interface_file(&self) -> Option<u8> {
+        if self.properties.act_en {
+            Some(self.interface_file_id)
+        } else {
+            None
+        }
+    }
+}
 impl Codec for FileHeader {
     type Error = StdError;
     fn encoded_size(&self) -> usize {
@@ -309,3 +359,103 @@ fn test_file_header() {
         &hex!("B8 13 01 02 DEADBEEF BAADFACE"),
     )
 }
+#[test]
+fn test_file_header_action_files() {
+    let mut header = FileHeader {
+        permissions: Permissions::from_byte(0),
+        properties: FileProperties {
+            act_en: true,
+            act_cond: ActionCondition::Write,
+            storage_class: StorageClass::Volatile,
+        },
+        alp_cmd_fid: 1,
+        interface_file_id: 2,
+        file_size: 0,
+        allocated_size: 0,
+    };
+    assert_eq!(header.action_command_file(), Some(1));
+    assert_eq!(header.interface_file(), Some(2));
+    header.properties.act_en = false;
+    assert_eq!(header.action_command_file(), None);
+    assert_eq!(header.interface_file(), None);
+}
+#[test]
+fn test_file_properties_act_cond_without_act_en() {
+    // The action condition is kept even when the action is disabled
+    for n in 0..8 {
+        for act_en in [false, true] {
+            let properties = FileProperties {
+                act_en,
+                act_cond: ActionCondition::from(n),
+                storage_class: StorageClass::Restorable,
+            };
+            assert_eq!(FileProperties::from_byte(properties.to_byte()), properties);
+        }
+    }
+}
+#[test]
+fn test_file_properties_nibbles() {
+    use std::convert::TryFrom;
+    for (n, act_cond, name) in [
+        (0, ActionCondition::List, "L"),
+        (1, ActionCondition::Read, "R"),
+        (2, ActionCondition::Write, "W"),
+        (3, ActionCondition::WriteFlush, "V"),
+    ] {
+        assert_eq!(ActionCondition::try_from(n), Ok(act_cond));
+        assert_eq!(act_cond as u8, n);
+        assert_eq!(act_cond.to_string(), name);
+    }
+    assert_eq!(ActionCondition::Unknown5.to_string(), "5");
+    assert_eq!(ActionCondition::try_from(5), Err(5));
+    assert_eq!(ActionCondition::try_from(8), Err(8));
+
+    for (n, storage_class, name) in [
+        (0, StorageClass::Transient, "T"),
+        (1, StorageClass::Volatile, "V"),
+        (2, StorageClass::Restorable, "R"),
+        (3, StorageClass::Permanent, "P"),
+    ] {
+        assert_eq!(StorageClass::try_from(n), Ok(storage_class));
+        assert_eq!(storage_class as u8, n);
+        assert_eq!(storage_class.to_string(), name);
+    }
+    assert_eq!(StorageClass::try_from(4), Err(4));
+}
+
+/// Whether `actual` equals `value` on the bits set in `mask` (all bits without mask), as done by
+/// the query comparisons with a value.
+///
+/// The data, value and mask must have the same length, otherwise nothing matches.
+pub fn masked_eq(actual: &[u8], value: &[u8], mask: Option<&[u8]>) -> bool {
+    if actual.len() != value.len() {
+        return false;
+    }
+    match mask {
+        None => actual == value,
+        Some(mask) => {
+            mask.len() == actual.len()
+                && actual
+                    .iter()
+                    .zip(value)
+                    .zip(mask)
+                    .all(|((a, v), m)| a & m == v & m)
+        }
+    }
+}
+#[test]
+fn test_masked_eq() {
+    assert!(masked_eq(&hex!("0102"), &hex!("0102"), None));
+    assert!(!masked_eq(&hex!("0102"), &hex!("0103"), None));
+    assert!(masked_eq(&hex!("0102"), &hex!("0103"), Some(&hex!("FFFC"))));
+    assert!(!masked_eq(
+        &hex!("0102"),
+        &hex!("0103"),
+        Some(&hex!("FF01"))
+    ));
+    assert!(masked_eq(&hex!("F0"), &hex!("0F"), Some(&hex!("00"))));
+    assert!(masked_eq(&[], &[], None));
+    // Length mismatches never match
+    assert!(!masked_eq(&hex!("01"), &hex!("0100"), None));
+    assert!(!masked_eq(&hex!("01"), &hex!("01"), Some(&hex!("FFFF"))));
+}
@@ -0,0 +1,214 @@
+use crate::spec::v1_2::{Action, Command};
+
+/// Difference on a single action between two commands
+#[derive(Clone, Debug, PartialEq)]
+pub enum ActionDiff {
+    /// Action of the old command (at `index`) missing from the new one
+    Removed { index: usize, action: Action },
+    /// Action of the new command (at `index`) missing from the old one
+    Added { index: usize, action: Action },
+    /// Action replaced by another one at the same place of the command
+    Changed {
+        old_index: usize,
+        new_index: usize,
+        old: Action,
+        new: Action,
+    },
+}
+impl std::fmt::Display for ActionDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Removed { index, action } => write!(f, "-[{}] {}", index, action),
+            Self::Added { index, action } => write!(f, "+[{}] {}", index, action),
+            Self::Changed {
+                old_index,
+                new_index,
+                old,
+                new,
+            } => write!(f, "-[{}] {}\n+[{}] {}", old_index, old, new_index, new),
+        }
+    }
+}
+
+/// Actions differing between two commands, in command order.
+///
+/// The actions common to both commands are matched first (longest common subsequence), the
+/// remaining actions being reported as changed when they sit at the same place in both commands,
+/// or as removed/added otherwise.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct CommandDiff {
+    pub actions: Vec<ActionDiff>,
+}
+impl CommandDiff {
+    pub fn new(old: &Command, new: &Command) -> Self {
+        let old = &old.actions[..];
+        let new = &new.actions[..];
+
+        // lcs[i][j]: length of the longest common subsequence of old[i..] and new[j..]
+        let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+        for i in (0..old.len()).rev() {
+            for j in (0..new.len()).rev() {
+                lcs[i][j] = if old[i] == new[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+
+        let mut actions = vec![];
+        let mut removed = vec![];
+        let mut added = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < old.len() || j < new.len() {
+            if i < old.len() && j < new.len() && old[i] == new[j] {
+                Self::flush(&mut actions, &mut removed, &mut added);
+                i += 1;
+                j += 1;
+            } else if j == new.len() || (i < old.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+                removed.push((i, old[i].clone()));
+                i += 1;
+            } else {
+                added.push((j, new[j].clone()));
+                j += 1;
+            }
+        }
+        Self::flush(&mut actions, &mut removed, &mut added);
+        Self { actions }
+    }
+
+    /// Pair the removed and added actions of a same gap between common actions
+    fn flush(
+        actions: &mut Vec<ActionDiff>,
+        removed: &mut Vec<(usize, Action)>,
+        added: &mut Vec<(usize, Action)>,
+    ) {
+        let mut removed = removed.drain(..);
+        let mut added = added.drain(..);
+        loop {
+            match (removed.next(), added.next()) {
+                (Some((old_index, old)), Some((new_index, new))) => {
+                    actions.push(ActionDiff::Changed {
+                        old_index,
+                        new_index,
+                        old,
+                        new,
+                    })
+                }
+                (Some((index, action)), None) => {
+                    actions.push(ActionDiff::Removed { index, action })
+                }
+                (None, Some((index, action))) => actions.push(ActionDiff::Added { index, action }),
+                (None, None) => break,
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+}
+impl std::fmt::Display for CommandDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for (i, action) in self.actions.iter().enumerate() {
+            if i != 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", action)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_identical() {
+        let cmd = Command {
+            actions: vec![Action::request_tag(true, 1), Action::nop(false, true)],
+        };
+        let diff = cmd.diff(&cmd);
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "");
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = Command {
+            actions: vec![
+                Action::request_tag(true, 1),
+                Action::read_file_data(false, true, 1, 0, 8),
+                Action::nop(false, false),
+                Action::test_exist_file(false, false, 2),
+            ],
+        };
+        let new = Command {
+            actions: vec![
+                Action::request_tag(true, 1),
+                Action::read_file_data(false, true, 1, 0, 4),
+                Action::test_exist_file(false, false, 2),
+                Action::nop(false, true),
+            ],
+        };
+        let diff = old.diff(&new);
+        assert_eq!(
+            diff.actions,
+            vec![
+                ActionDiff::Changed {
+                    old_index: 1,
+                    new_index: 1,
+                    old: Action::read_file_data(false, true, 1, 0, 8),
+                    new: Action::read_file_data(false, true, 1, 0, 4),
+                },
+                ActionDiff::Removed {
+                    index: 2,
+                    action: Action::nop(false, false),
+                },
+                ActionDiff::Added {
+                    index: 3,
+                    action: Action::nop(false, true),
+                },
+            ]
+        );
+        assert_eq!(
+            diff.to_string(),
+            format!(
+                "-[1] {}\n+[1] {}\n-[2] {}\n+[3] {}",
+                Action::read_file_data(false, true, 1, 0, 8),
+                Action::read_file_data(false, true, 1, 0, 4),
+                Action::nop(false, false),
+                Action::nop(false, true),
+            )
+        );
+    }
+
+    #[test]
+    fn test_diff_length_mismatch() {
+        let old = Command {
+            actions: vec![Action::nop(false, false)],
+        };
+        let new = Command {
+            actions: vec![
+                Action::nop(false, false),
+                Action::request_tag(true, 1),
+                Action::request_tag(true, 2),
+            ],
+        };
+        assert_eq!(
+            old.diff(&new).actions,
+            vec![
+                ActionDiff::Added {
+                    index: 1,
+                    action: Action::request_tag(true, 1),
+                },
+                ActionDiff::Added {
+                    index: 2,
+                    action: Action::request_tag(true, 2),
+                },
+            ]
+        );
+        assert_eq!(new.diff(&old).actions.len(), 2);
+    }
+}
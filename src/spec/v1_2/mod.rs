@@ -1,19 +1,42 @@
 #[cfg(test)]
 use hex_literal::hex;
+use std::ops::ControlFlow;
 
 /// ALP basic Actions used to build Commands
 pub mod action;
+#[cfg(test)]
+mod arbitrary;
+/// Reassembly of files read in pieces
+pub mod assembler;
+/// Helpers to build Commands
+pub mod builder;
+/// Dash7 compressed format codec
+pub mod compressed;
 /// Dash7 specific items (most of the ALP protocol could be in theory be used over any
 /// communication link)
 pub mod dash7;
 /// Filesystem related items
 pub mod data;
+/// Differences between commands
+pub mod diff;
 /// Operands used to build the ALP Actions
 pub mod operand;
+/// Decoding of unframed streams of commands
+pub mod stream;
+/// Strict (spec conformance checking) decoding
+pub mod strict;
 /// ALP variable int codec implementation
 pub mod varint;
+/// Per action kind dispatch of the actions of a command
+pub mod visitor;
 pub use crate::codec::{Codec, WithOffset, WithSize};
 pub use action::Action;
+pub use assembler::FileDataAssembler;
+pub use builder::CommandBuilder;
+pub use diff::CommandDiff;
+pub use stream::{decode_stream, resync};
+pub use strict::{StrictDecodeError, StrictViolation};
+pub use visitor::ActionVisitor;
 
 // TODO Verify each item's name against the SPEC
 
@@ -34,6 +57,7 @@ pub use action::Action;
 // ===============================================================================
 /// ALP request that can be sent to an ALP compatible device.
 #[derive(Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Command {
     // Does that impact application that don't use the structure?
     pub actions: Vec<Action>,
@@ -42,24 +66,477 @@ pub struct Command {
 impl std::fmt::Display for Command {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "[")?;
-        let end = self.actions.len() - 1;
         for (i, action) in self.actions.iter().enumerate() {
-            write!(f, "{}", action)?;
-            if i != end {
+            if i != 0 {
                 write!(f, "; ")?;
             }
+            write!(f, "{}", action)?;
         }
         write!(f, "]")
     }
 }
 
+/// Displays a command followed by its encoding, as space separated hexadecimal bytes (eg.
+/// `[RTAG[E](66); NOP[GR]]  (B4 42 C0)`).
+#[derive(Clone, Copy, Debug)]
+pub struct DisplayWithHex<'a>(pub &'a Command);
+impl<'a> std::fmt::Display for DisplayWithHex<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}  (", self.0)?;
+        for (i, byte) in self.0.encode().iter().enumerate() {
+            if i != 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{:02X}", byte)?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl From<Vec<Action>> for Command {
+    fn from(actions: Vec<Action>) -> Self {
+        Self { actions }
+    }
+}
+impl From<&[Action]> for Command {
+    fn from(actions: &[Action]) -> Self {
+        Self {
+            actions: actions.to_vec(),
+        }
+    }
+}
+impl std::iter::FromIterator<Action> for Command {
+    fn from_iter<I: IntoIterator<Item = Action>>(iter: I) -> Self {
+        Self {
+            actions: iter.into_iter().collect(),
+        }
+    }
+}
+impl Extend<Action> for Command {
+    fn extend<I: IntoIterator<Item = Action>>(&mut self, iter: I) {
+        self.actions.extend(iter)
+    }
+}
+impl From<Command> for Vec<u8> {
+    fn from(command: Command) -> Self {
+        command.encode().into_vec()
+    }
+}
+impl std::convert::TryFrom<&[u8]> for Command {
+    type Error = WithOffset<CommandParseFail>;
+    fn try_from(out: &[u8]) -> Result<Self, Self::Error> {
+        Self::decode(out)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct CommandParseFail {
     pub actions: Vec<Action>,
     pub error: action::ActionDecodingError,
 }
 
+/// Error returned when a command cannot be split into chunks fitting a frame size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChunkingError {
+    /// The action at `index` needs `size` bytes, which does not fit in a chunk once prefixed by
+    /// its chunk action.
+    ActionTooBig { index: usize, size: usize },
+}
+
+/// Reason a command fails [validation](struct.Command.html#method.validate).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CommandValidationError {
+    /// An operand of the action at `index` cannot be encoded
+    Operand {
+        index: usize,
+        error: action::OperandValidationError,
+    },
+    /// The query of the action at `index` is inconsistent
+    Query {
+        index: usize,
+        error: operand::QueryValidationError,
+    },
+    /// The action at `index` is a [Chunk](action::Chunk) that is not the first action of the
+    /// command
+    MisplacedChunk { index: usize },
+    /// The action at `index` is a second [RequestTag](action::RequestTag): a request can only
+    /// have one identifier
+    MultipleRequestTags { index: usize },
+}
+
+/// Reason a command fails a [security check](struct.Command.html#method.check_security).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SecurityError {
+    /// The forwarding action at `index` uses the `actual` NLS method, which does not satisfy the
+    /// required one
+    NlsMethod {
+        index: usize,
+        actual: dash7::NlsMethod,
+    },
+    /// The [IndirectForward](action::IndirectForward) at `index` takes its NLS method from the
+    /// file `interface_file_id`, which cannot be checked from the command
+    UncheckedInterface { index: usize, interface_file_id: u8 },
+    /// The [Forward](action::Forward) at `index` uses the custom interface `interface_id`, whose
+    /// security cannot be checked by this library
+    CustomInterface { index: usize, interface_id: u8 },
+}
+impl std::fmt::Display for SecurityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::NlsMethod { index, actual } => {
+                write!(f, "action {} uses the NLS method {:?}", index, actual)
+            }
+            Self::UncheckedInterface {
+                index,
+                interface_file_id,
+            } => write!(
+                f,
+                "action {} uses the unchecked interface file {}",
+                index, interface_file_id
+            ),
+            Self::CustomInterface {
+                index,
+                interface_id,
+            } => write!(
+                f,
+                "action {} uses the custom interface 0x{:02X}",
+                index, interface_id
+            ),
+        }
+    }
+}
+
+/// Error of the decoders reading from a `std::io` source, or expecting an exact command.
+#[derive(Debug)]
+pub enum DecodeError {
+    Io(std::io::Error),
+    Parse(WithOffset<CommandParseFail>),
+    /// The input ends with this many bytes not forming a complete action
+    TrailingBytes(usize),
+}
+impl From<std::io::Error> for DecodeError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+impl From<WithOffset<CommandParseFail>> for DecodeError {
+    fn from(e: WithOffset<CommandParseFail>) -> Self {
+        Self::Parse(e)
+    }
+}
+
+/// Reason a frame does not survive a decoding/encoding round trip (see [check_roundtrip]).
+#[derive(Debug, Clone, PartialEq)]
+pub enum RoundtripMismatch {
+    /// The frame is not a valid command
+    Decode(WithOffset<CommandParseFail>),
+    /// The re-encoded command differs from the frame, first at `offset`. A byte is `None` when
+    /// `offset` is past the end of its buffer.
+    Byte {
+        offset: usize,
+        input: Option<u8>,
+        encoded: Option<u8>,
+    },
+}
+impl std::fmt::Display for RoundtripMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let byte = |b: &Option<u8>| match b {
+            Some(b) => format!("0x{:02X}", b),
+            None => "end".to_string(),
+        };
+        match self {
+            Self::Decode(e) => write!(f, "decoding failed at offset {}", e.offset),
+            Self::Byte {
+                offset,
+                input,
+                encoded,
+            } => write!(
+                f,
+                "at offset {}: input {}, encoded {}",
+                offset,
+                byte(input),
+                byte(encoded)
+            ),
+        }
+    }
+}
+
+/// Decodes the actions of a command one by one, handing each of them to `on_action` along with
+/// the offset of its first byte, without collecting them.
+///
+/// Returns the number of bytes consumed, which is smaller than `out.len()` if `on_action` stops
+/// the decoding.
+fn decode_actions(
+    out: &[u8],
+    max_actions: Option<usize>,
+    ignore_trailing_zeros: bool,
+    overrides: Option<&action::OpCodeMap>,
+    mut on_action: impl FnMut(usize, Action) -> ControlFlow<()>,
+) -> Result<usize, WithOffset<action::ActionDecodingError>> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::trace_span!("decode_command", size = out.len()).entered();
+    // End of the actions: the trailing zeros are padding when ignored
+    let end = if ignore_trailing_zeros {
+        out.iter().rposition(|b| *b != 0).map_or(0, |last| last + 1)
+    } else {
+        out.len()
+    };
+    let mut count = 0;
+    let mut offset = 0;
+    loop {
+        if offset >= end {
+            break;
+        }
+        if Some(count) == max_actions {
+            return Err(WithOffset::new(
+                offset,
+                action::ActionDecodingError::TooManyActions,
+            ));
+        }
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!("decode_action", offset).entered();
+        let decoded = match overrides.and_then(|map| map.get(out[offset] & 0x3F)) {
+            Some(op_code) => Action::decode_as(op_code, &out[offset..]),
+            None => Action::decode(&out[offset..]),
+        };
+        match decoded {
+            Ok(WithSize { value, size }) => {
+                #[cfg(feature = "tracing")]
+                tracing::trace!(size, "decoded {}", value);
+                let start = offset;
+                count += 1;
+                offset += size;
+                if on_action(start, value).is_break() {
+                    break;
+                }
+            }
+            Err(error) => {
+                let WithOffset { offset: off, value } = error;
+                #[cfg(feature = "tracing")]
+                tracing::trace!(offset = offset + off, error = ?value, "decoding failed");
+                return Err(WithOffset::new(offset + off, value));
+            }
+        }
+    }
+    Ok(offset)
+}
+
+/// Decodes a command action by action, handing each of them to `on_action` instead of
+/// collecting them, to process big commands with bounded memory.
+///
+/// `on_action` can stop the decoding by returning [ControlFlow::Break]. Returns the number of
+/// bytes consumed, up to the end of the last decoded action.
+pub fn decode_events(
+    out: &[u8],
+    mut on_action: impl FnMut(Action) -> ControlFlow<()>,
+) -> Result<usize, WithOffset<action::ActionDecodingError>> {
+    decode_actions(out, None, false, None, |_, action| on_action(action))
+}
+
+/// Decodes a command and re-encodes it, checking that the encoding gives back `bytes`.
+pub fn check_roundtrip(bytes: &[u8]) -> Result<(), RoundtripMismatch> {
+    let encoded = Command::decode(bytes)
+        .map_err(RoundtripMismatch::Decode)?
+        .encode();
+    let size = bytes.len().max(encoded.len());
+    match (0..size).find(|&i| bytes.get(i) != encoded.get(i)) {
+        None => Ok(()),
+        Some(offset) => Err(RoundtripMismatch::Byte {
+            offset,
+            input: bytes.get(offset).copied(),
+            encoded: encoded.get(offset).copied(),
+        }),
+    }
+}
+
+/// Machine readable JSON representation, meant to be written one command per line.
+///
+/// A command is an array of actions, each action being an object holding its fields and its
+/// operation name under the `op` key, e.g. `{"op":"ReadFileData","file_id":1,...}`. Unlike the
+/// `Display` output, this format is meant to be parsed back.
+#[cfg(feature = "serde")]
+impl Command {
+    pub fn to_ndjson_value(&self) -> serde_json::Value {
+        serde_json::to_value(&self.actions).expect("actions are always serializable to JSON")
+    }
+    pub fn from_ndjson_value(value: serde_json::Value) -> Result<Self, serde_json::Error> {
+        Ok(Self {
+            actions: serde_json::from_value(value)?,
+        })
+    }
+}
+#[cfg(feature = "serde")]
+#[test]
+fn test_command_ndjson() {
+    let cmd = Command {
+        actions: vec![
+            Action::request_tag(true, 66),
+            Action::read_file_data(false, true, 1, 2, 3),
+            Action::chunk(action::Chunk::Start),
+            Action::logic(action::Logic::Nand),
+            Action::status(action::Status::Action(operand::ActionStatus {
+                action_id: 2,
+                status: operand::StatusCode::UnknownOperation,
+            })),
+            Action::write_file_data(true, false, 4, 5, &hex!("0102")[..]),
+            Action::action_query(true, false, operand::Query::in_range(1, 2, 3, 4)),
+            Action::PermissionRequest(action::PermissionRequest {
+                group: false,
+                resp: false,
+                level: operand::permission_level::ROOT,
+                permission: operand::Permission::Rfu(3, Box::new([4])),
+            }),
+        ],
+    };
+    let value = cmd.to_ndjson_value();
+    assert_eq!(
+        value[1],
+        serde_json::json!({
+            "op": "ReadFileData",
+            "group": false,
+            "resp": true,
+            "file_id": 1,
+            "offset": 2,
+            "size": 3,
+        })
+    );
+    let line = value.to_string();
+    assert!(!line.contains('\n'));
+    let parsed: serde_json::Value = serde_json::from_str(&line).unwrap();
+    assert_eq!(Command::from_ndjson_value(parsed).unwrap(), cmd);
+}
+
+/// Mutable access to the actions of a command, checking the command once done.
+///
+/// Returned by [Command::actions_mut]. When dropped, the command is
+/// [validated](struct.Command.html#method.validate): a failure panics in debug builds, and is
+/// logged (with the `tracing` feature) in release builds.
+pub struct ActionsGuard<'a> {
+    command: &'a mut Command,
+}
+impl std::ops::Deref for ActionsGuard<'_> {
+    type Target = Vec<Action>;
+    fn deref(&self) -> &Self::Target {
+        &self.command.actions
+    }
+}
+impl std::ops::DerefMut for ActionsGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.command.actions
+    }
+}
+impl Drop for ActionsGuard<'_> {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            return;
+        }
+        #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+        if let Err(e) = self.command.validate() {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(error = ?e, "invalid command after edition");
+            debug_assert!(false, "invalid command after edition: {:?}", e);
+        }
+    }
+}
+
 impl Command {
+    /// Appends an action to the command
+    pub fn push(&mut self, action: Action) {
+        self.actions.push(action)
+    }
+    /// Splits the command in two at the action `index`, returning the actions from `index`
+    /// onwards as a new command (see [Vec::split_off]).
+    /// # Panics
+    /// Panics if `index` is greater than the number of actions.
+    pub fn split_off(&mut self, index: usize) -> Command {
+        Command {
+            actions: self.actions.split_off(index),
+        }
+    }
+    /// Mutable access to the actions, the command being validated once the returned guard is
+    /// dropped (see [ActionsGuard]).
+    pub fn actions_mut(&mut self) -> ActionsGuard<'_> {
+        ActionsGuard { command: self }
+    }
+    /// Checks that the command can be encoded and is consistent:
+    /// - the offsets and sizes of the actions fit in a varint,
+    /// - the queries are consistent (see [Query::validate](operand::Query::validate)),
+    /// - a [Chunk](action::Chunk) action can only be the first action of the command,
+    /// - a command holds at most one [RequestTag](action::RequestTag) action.
+    pub fn validate(&self) -> Result<(), CommandValidationError> {
+        let mut request_tagged = false;
+        for (index, action) in self.actions.iter().enumerate() {
+            match action {
+                Action::ReadFileData(action) => action
+                    .validate()
+                    .map_err(|error| CommandValidationError::Operand { index, error })?,
+                Action::WriteFileData(action) | Action::ReturnFileData(action) => action
+                    .validate()
+                    .map_err(|error| CommandValidationError::Operand { index, error })?,
+                Action::ActionQuery(action)
+                | Action::BreakQuery(action)
+                | Action::VerifyChecksum(action) => action
+                    .query
+                    .validate()
+                    .map_err(|error| CommandValidationError::Query { index, error })?,
+                Action::Chunk(_) if index != 0 => {
+                    return Err(CommandValidationError::MisplacedChunk { index })
+                }
+                Action::RequestTag(_) if request_tagged => {
+                    return Err(CommandValidationError::MultipleRequestTags { index })
+                }
+                Action::RequestTag(_) => request_tagged = true,
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+    /// Checks that every dash7 transmission requested by the command uses an NLS method
+    /// compatible with `required`.
+    ///
+    /// The [Forward](action::Forward) actions to a dash7 interface and the overloaded
+    /// [IndirectForward](action::IndirectForward) actions must use a method that
+    /// [satisfies](dash7::NlsMethod::satisfies) `required`. A non-overloaded `IndirectForward` is
+    /// refused, as its configuration lives in a file, and so is a `Forward` to a custom
+    /// interface. Requiring [NlsMethod::None](dash7::NlsMethod::None) accepts any command.
+    pub fn check_security(&self, required: dash7::NlsMethod) -> Result<(), SecurityError> {
+        if required == dash7::NlsMethod::None {
+            return Ok(());
+        }
+        for (index, action) in self.actions.iter().enumerate() {
+            let actual = match action {
+                Action::Forward(action::Forward {
+                    conf: operand::InterfaceConfiguration::D7asp(conf),
+                    ..
+                }) => conf.nls_method,
+                Action::Forward(action::Forward {
+                    conf: operand::InterfaceConfiguration::Custom(conf),
+                    ..
+                }) => {
+                    return Err(SecurityError::CustomInterface {
+                        index,
+                        interface_id: conf.interface_id(),
+                    })
+                }
+                Action::IndirectForward(action::IndirectForward { interface, .. }) => {
+                    match interface {
+                        operand::IndirectInterface::Overloaded(interface) => interface.nls_method,
+                        operand::IndirectInterface::NonOverloaded(interface) => {
+                            return Err(SecurityError::UncheckedInterface {
+                                index,
+                                interface_file_id: interface.interface_file_id,
+                            })
+                        }
+                    }
+                }
+                _ => continue,
+            };
+            if !actual.satisfies(required) {
+                return Err(SecurityError::NlsMethod { index, actual });
+            }
+        }
+        Ok(())
+    }
     pub fn encoded_size(&self) -> usize {
         self.actions.iter().map(|act| act.encoded_size()).sum()
     }
@@ -81,60 +558,491 @@ impl Command {
         unsafe { self.encode_in(&mut data) };
         data
     }
+    /// Encodes the command at the head of a possibly uninitialized buffer, returning the number
+    /// of bytes written (and thus initialized).
+    ///
+    /// Only the bytes of the encoded command are written, which saves zeroing the whole buffer
+    /// when it is much bigger than the command.
+    /// # Panics
+    /// Panics if `out` is smaller than [encoded_size](#method.encoded_size).
+    pub fn encode_in_uninit(&self, out: &mut [std::mem::MaybeUninit<u8>]) -> usize {
+        let size = self.encoded_size();
+        let out = &mut out[..size];
+        // The actions OR their flags into their first byte: they need zeroed memory.
+        for byte in out.iter_mut() {
+            byte.write(0);
+        }
+        // SAFETY: The `size` bytes have just been initialized, and MaybeUninit<u8> has the same
+        // layout as u8.
+        let out = unsafe { &mut *(out as *mut [std::mem::MaybeUninit<u8>] as *mut [u8]) };
+        unsafe { self.encode_in(out) }
+    }
+    /// Byte range of each action in the [encode](#method.encode) output, in action order.
+    ///
+    /// An action can be replaced in place in the encoded command by another action of the same
+    /// encoded size.
+    pub fn action_byte_ranges(&self) -> Vec<std::ops::Range<usize>> {
+        let mut offset = 0;
+        self.actions
+            .iter()
+            .map(|action| {
+                let start = offset;
+                offset += action.encoded_size();
+                start..offset
+            })
+            .collect()
+    }
+    /// Opcodes of the responses expected for this command, in action order (see
+    /// [Action::expected_response](action/enum.Action.html#method.expected_response)).
+    ///
+    /// Each entry holds the index of the requesting action.
+    pub fn expected_responses(&self) -> Vec<(usize, action::OpCode)> {
+        self.actions
+            .iter()
+            .enumerate()
+            .filter_map(|(i, action)| action.expected_response().map(|op_code| (i, op_code)))
+            .collect()
+    }
+    /// Encoded command as an uppercase hexadecimal string.
+    pub fn to_hex(&self) -> String {
+        hex::encode_upper(self.encode())
+    }
+    /// Encoded command as space separated uppercase hexadecimal bytes, the actions being
+    /// separated by two spaces (eg. `B4 42  41 00 00 08  C0`).
+    pub fn encode_hex_spaced(&self) -> String {
+        self.actions
+            .iter()
+            .map(|action| {
+                action
+                    .encode()
+                    .iter()
+                    .map(|byte| format!("{:02X}", byte))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+    }
     pub fn decode(out: &[u8]) -> Result<Self, WithOffset<CommandParseFail>> {
+        Self::decode_with(out, None, false, None, None)
+    }
+    /// Decodes a command, considering a run of zero bytes at the very end of `out` as padding
+    /// rather than `Nop` actions.
+    ///
+    /// Intentional trailing `Nop` actions without flags cannot be told apart from padding and
+    /// are thus dropped too. `Nop` actions followed by any non zero byte are kept.
+    pub fn decode_ignoring_trailing_zeros(
+        out: &[u8],
+    ) -> Result<Self, WithOffset<CommandParseFail>> {
+        Self::decode_with(out, None, true, None, None)
+    }
+    /// Decodes a command that must span the whole of `out`.
+    ///
+    /// Unlike [decode](#method.decode), a truncated last action is reported as
+    /// [TrailingBytes](DecodeError::TrailingBytes) (the number of bytes following the last
+    /// complete action) rather than as a parsing failure of that action.
+    pub fn decode_exact(out: &[u8]) -> Result<Self, DecodeError> {
         let mut actions = vec![];
         let mut offset = 0;
-        loop {
-            if offset == out.len() {
-                break;
-            }
+        while offset < out.len() {
             match Action::decode(&out[offset..]) {
                 Ok(WithSize { value, size }) => {
                     actions.push(value);
                     offset += size;
                 }
-                Err(error) => {
-                    let WithOffset { offset: off, value } = error;
-                    return Err(WithOffset {
-                        offset: offset + off,
-                        value: CommandParseFail {
+                Err(WithOffset { value, .. }) if value.missing_bytes().is_some() => {
+                    return Err(DecodeError::TrailingBytes(out.len() - offset));
+                }
+                Err(WithOffset { offset: off, value }) => {
+                    return Err(DecodeError::Parse(WithOffset::new(
+                        offset + off,
+                        CommandParseFail {
                             actions,
                             error: value,
                         },
-                    });
+                    )));
                 }
             }
         }
         Ok(Self { actions })
     }
+    /// Decodes a command, failing with
+    /// [TooManyActions](action::ActionDecodingError::TooManyActions) as soon as it holds more
+    /// than `max_actions` actions.
+    ///
+    /// Meant to be used on untrusted input, where [decode](#method.decode) could be made to
+    /// allocate one action per input byte.
+    pub fn decode_limited(
+        out: &[u8],
+        max_actions: usize,
+    ) -> Result<Self, WithOffset<CommandParseFail>> {
+        Self::decode_with(out, Some(max_actions), false, None, None)
+    }
+    /// Decodes a command, failing on any content the specification reserves for future use
+    /// (e.g. RFU retry modes or permission types) or any bit ignored by the decoder.
+    ///
+    /// [decode](#method.decode) tolerates them, this is meant to check the conformance of a
+    /// device rather than to talk to it.
+    pub fn decode_strict(out: &[u8]) -> Result<Self, StrictDecodeError> {
+        strict::decode_strict(out)
+    }
+    /// Decodes a command, decoding the actions whose raw opcode is overridden by `overrides`
+    /// as the action it is mapped onto.
+    ///
+    /// This is not spec conformant and only meant to parse the traffic of prototype devices
+    /// using vendor opcodes. See [OpCodeMap](action::OpCodeMap).
+    pub fn decode_with_overrides(
+        out: &[u8],
+        overrides: &action::OpCodeMap,
+    ) -> Result<Self, WithOffset<CommandParseFail>> {
+        Self::decode_with(out, None, false, Some(overrides), None)
+    }
+    /// Decodes a command, pairing each action with the offset of its first byte in `out`.
+    ///
+    /// The offsets are the ones the actions were actually read at, which can differ from the
+    /// ones derived from their [encoded size](#method.action_byte_ranges) when the input does not
+    /// use the shortest encoding (e.g. varints).
+    pub fn decode_annotated(
+        out: &[u8],
+    ) -> Result<Vec<(usize, Action)>, WithOffset<CommandParseFail>> {
+        let mut starts = vec![];
+        let Self { actions } = Self::decode_with(out, None, false, None, Some(&mut starts))?;
+        Ok(starts.into_iter().zip(actions).collect())
+    }
+    fn decode_with(
+        out: &[u8],
+        max_actions: Option<usize>,
+        ignore_trailing_zeros: bool,
+        overrides: Option<&action::OpCodeMap>,
+        mut starts: Option<&mut Vec<usize>>,
+    ) -> Result<Self, WithOffset<CommandParseFail>> {
+        let mut actions = vec![];
+        let ret = decode_actions(
+            out,
+            max_actions,
+            ignore_trailing_zeros,
+            overrides,
+            |offset, action| {
+                if let Some(starts) = starts.as_mut() {
+                    starts.push(offset);
+                }
+                actions.push(action);
+                ControlFlow::Continue(())
+            },
+        );
+        match ret {
+            Ok(_) => Ok(Self { actions }),
+            Err(e) => Err(e.map_value(|error| CommandParseFail { actions, error })),
+        }
+    }
 
-    pub fn request_id(&self) -> Option<u8> {
-        for action in self.actions.iter() {
-            if let Action::RequestTag(action::RequestTag { id, .. }) = action {
-                return Some(*id);
+    /// Merges immediately adjacent [ReadFileData](action::ReadFileData) actions that read
+    /// contiguous ranges of the same file with the same flags.
+    ///
+    /// Reads separated by any other action are left untouched, so that queries or logic
+    /// actions keep applying to the same reads.
+    pub fn coalesce_reads(&mut self) {
+        let mut actions: Vec<Action> = Vec::with_capacity(self.actions.len());
+        for action in self.actions.drain(..) {
+            if let (Some(Action::ReadFileData(a)), Action::ReadFileData(b)) =
+                (actions.last_mut(), &action)
+            {
+                if a.file_id == b.file_id
+                    && a.group == b.group
+                    && a.resp == b.resp
+                    && a.offset.checked_add(a.size) == Some(b.offset)
+                {
+                    if let Some(size) = a.size.checked_add(b.size) {
+                        if size <= varint::MAX {
+                            a.size = size;
+                            continue;
+                        }
+                    }
+                }
             }
+            actions.push(action);
         }
-        None
+        self.actions = actions;
     }
 
-    pub fn response_id(&self) -> Option<u8> {
-        for action in self.actions.iter() {
-            if let Action::ResponseTag(action::ResponseTag { id, .. }) = action {
-                return Some(*id);
+    /// Reads `reader` to its end and decodes its content as a single command.
+    pub fn read_from<R: std::io::Read>(mut reader: R) -> Result<Self, DecodeError> {
+        let mut data = vec![];
+        reader.read_to_end(&mut data)?;
+        Ok(Self::decode(&data)?)
+    }
+
+    /// Minimum number of bytes that must be appended to `partial` to complete the action it
+    /// ends with.
+    ///
+    /// Returns `None` if `partial` already is a complete command, or if it is malformed in a
+    /// way more bytes cannot fix.
+    pub fn bytes_needed(partial: &[u8]) -> Option<usize> {
+        match Self::decode(partial) {
+            Ok(_) => None,
+            Err(WithOffset { value, .. }) => value.error.missing_bytes(),
+        }
+    }
+
+    /// Decodes the request id and the opcode of the first action following it, without
+    /// decoding the rest of the command.
+    ///
+    /// This assumes the usual tag first layout: the request id is only looked for in a
+    /// [RequestTag](action::RequestTag) starting the command, and is `None` otherwise.
+    pub fn peek_header(
+        out: &[u8],
+    ) -> Result<(Option<u8>, action::OpCode), WithOffset<action::ActionDecodingError>> {
+        let opcode = |offset: usize| match out.get(offset) {
+            None => Err(WithOffset::new(offset, action::ActionDecodingError::NoData)),
+            Some(byte) => action::OpCode::from(byte & 0x3F).map_err(|n| {
+                WithOffset::new(offset, action::ActionDecodingError::UnknownOpCode(n))
+            }),
+        };
+        match opcode(0)? {
+            action::OpCode::RequestTag => {
+                let WithSize { value, size } = action::RequestTag::decode(out)
+                    .map_err(|e| e.map_value(action::ActionDecodingError::RequestTag))?;
+                Ok((Some(value.id), opcode(size)?))
             }
+            op => Ok((None, op)),
         }
-        None
     }
 
-    pub fn is_last_response(&self) -> bool {
-        for action in self.actions.iter() {
-            if let Action::ResponseTag(action::ResponseTag { eop, .. }) = action {
-                return *eop;
+    /// Removes the actions identical to the action preceding them.
+    ///
+    /// Unlike [canonicalize](#method.canonicalize), only exact adjacent repeats are removed. Note
+    /// that a removed action requesting a response will not be answered twice anymore.
+    pub fn dedup_actions(&mut self) {
+        self.actions.dedup();
+    }
+
+    /// Rewrites the command into a canonical form, so that commands differing only cosmetically
+    /// compare equal.
+    ///
+    /// The applied transforms are:
+    /// - Trailing `Nop` actions not requesting a response are removed: they neither act nor
+    ///   answer.
+    /// - The group flag of the last action is cleared, as there is no next action to group
+    ///   with.
+    /// - Runs of consecutive ungrouped `ReadFileData` actions are sorted by file id, then by
+    ///   offset. A read that follows a query, a logic action or a grouped action is left in
+    ///   place, as it may be conditioned by it. Reads do not modify anything, so their order
+    ///   only affects the order of the returned data within the response.
+    pub fn canonicalize(&mut self) {
+        while let Some(Action::Nop(action::Nop { resp: false, .. })) = self.actions.last() {
+            self.actions.pop();
+        }
+        if let Some(group) = self
+            .actions
+            .last_mut()
+            .and_then(|action| action.group_mut())
+        {
+            *group = false;
+        }
+
+        // Sort key of the ungrouped reads
+        let free_read_key = |action: &Action| match action {
+            Action::ReadFileData(read) if !read.group => Some((read.file_id, read.offset)),
+            _ => None,
+        };
+        let is_free_read = |action: &Action| free_read_key(action).is_some();
+        let mut start = 0;
+        while start < self.actions.len() {
+            if !is_free_read(&self.actions[start]) {
+                start += 1;
+                continue;
+            }
+            let mut end = start;
+            while end < self.actions.len() && is_free_read(&self.actions[end]) {
+                end += 1;
+            }
+            let mut first = start;
+            if first > 0 {
+                let previous = &mut self.actions[first - 1];
+                let conditional = matches!(
+                    previous,
+                    Action::ActionQuery(_)
+                        | Action::BreakQuery(_)
+                        | Action::VerifyChecksum(_)
+                        | Action::Logic(_)
+                ) || previous.group_mut().map(|group| *group).unwrap_or(false);
+                if conditional {
+                    first += 1;
+                }
+            }
+            self.actions[first..end].sort_by_key(free_read_key);
+            start = end;
+        }
+    }
+
+    /// Iterates over the actions having the given opcode
+    pub fn filter_op(&self, op: action::OpCode) -> impl Iterator<Item = &Action> {
+        self.actions
+            .iter()
+            .filter(move |action| action.op_code() == op)
+    }
+
+    /// Number of actions having the given opcode
+    pub fn count_op(&self, op: action::OpCode) -> usize {
+        self.filter_op(op).count()
+    }
+
+    /// Whether the encoded command fits in a frame of `mtu` bytes.
+    pub fn fits_in(&self, mtu: usize) -> bool {
+        self.encoded_size() <= mtu
+    }
+
+    /// Splits the command into commands of at most `mtu` bytes each.
+    ///
+    /// If the command already fits, it is returned as is. Otherwise each sub-command starts
+    /// with a [Chunk](action::Chunk) action (`Start`, then `Continue`, then `End`) followed by
+    /// as many actions as fit. Actions are never split: an action too big to fit in a chunk on
+    /// its own is an error.
+    pub fn chunk_for_mtu(&self, mtu: usize) -> Result<Vec<Command>, ChunkingError> {
+        if self.fits_in(mtu) {
+            return Ok(vec![self.clone()]);
+        }
+        let chunk_size = Action::Chunk(action::Chunk::Continue).encoded_size();
+        let mut chunks: Vec<Command> = vec![];
+        let mut current = vec![];
+        let mut current_size = chunk_size;
+        for (index, action) in self.actions.iter().enumerate() {
+            let size = action.encoded_size();
+            if chunk_size + size > mtu {
+                return Err(ChunkingError::ActionTooBig { index, size });
             }
+            if current_size + size > mtu {
+                chunks.push(Command {
+                    actions: std::mem::take(&mut current),
+                });
+                current_size = chunk_size;
+            }
+            current.push(action.clone());
+            current_size += size;
+        }
+        chunks.push(Command { actions: current });
+        let last = chunks.len() - 1;
+        for (i, chunk) in chunks.iter_mut().enumerate() {
+            let step = if i == 0 {
+                action::Chunk::Start
+            } else if i == last {
+                action::Chunk::End
+            } else {
+                action::Chunk::Continue
+            };
+            chunk.actions.insert(0, Action::Chunk(step));
+        }
+        Ok(chunks)
+    }
+
+    /// First request tag of the command, if any
+    pub fn request_tag(&self) -> Option<&action::RequestTag> {
+        self.actions.iter().find_map(|action| match action {
+            Action::RequestTag(tag) => Some(tag),
+            _ => None,
+        })
+    }
+
+    /// First response tag of the command, if any
+    pub fn response_tag(&self) -> Option<&action::ResponseTag> {
+        self.actions.iter().find_map(|action| match action {
+            Action::ResponseTag(tag) => Some(tag),
+            _ => None,
+        })
+    }
+
+    /// Payload of the first interface [Status](action::Status) of the command, if any: the
+    /// reception conditions of a response.
+    pub fn interface_status(&self) -> Option<&operand::InterfaceStatus> {
+        self.actions.iter().find_map(|action| match action {
+            Action::Status(action::Status::Interface(status)) => Some(status),
+            _ => None,
+        })
+    }
+
+    /// Start of the response to this request: a command holding the
+    /// [ResponseTag](action::ResponseTag) matching its [request tag](#method.request_tag) (same
+    /// `id` and `eop`, no error), or an empty command if the request is not tagged.
+    pub fn response_skeleton(&self) -> Command {
+        Command {
+            actions: self
+                .request_tag()
+                .map(|tag| Action::response_tag(tag.eop, false, tag.id))
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    /// Dispatches each action of the command, in order, to its method of `visitor`.
+    pub fn accept<V: ActionVisitor + ?Sized>(&self, visitor: &mut V) {
+        for action in self.actions.iter() {
+            action.accept(visitor);
         }
-        false
+    }
+
+    /// Actions added, removed or changed in `other` compared to this command.
+    pub fn diff(&self, other: &Command) -> CommandDiff {
+        CommandDiff::new(self, other)
+    }
+
+    /// Compares two commands, ignoring the `id` of their [RequestTag](action::RequestTag) and
+    /// [ResponseTag](action::ResponseTag) actions.
+    ///
+    /// Every other field is compared, including the `eop` flag of the tags and the `err` flag of
+    /// the response tags. Meant for tests comparing a command against a template whose tag ids
+    /// are assigned at runtime.
+    pub fn eq_ignoring_tags(&self, other: &Command) -> bool {
+        self.actions.len() == other.actions.len()
+            && self
+                .actions
+                .iter()
+                .zip(other.actions.iter())
+                .all(|(a, b)| match (a, b) {
+                    (Action::RequestTag(a), Action::RequestTag(b)) => a.eop == b.eop,
+                    (Action::ResponseTag(a), Action::ResponseTag(b)) => {
+                        a.eop == b.eop && a.err == b.err
+                    }
+                    (a, b) => a == b,
+                })
+    }
+
+    pub fn request_id(&self) -> Option<u8> {
+        self.request_tag().map(|tag| tag.id)
+    }
+
+    pub fn response_id(&self) -> Option<u8> {
+        self.response_tag().map(|tag| tag.id)
+    }
+
+    /// Identifiers of every request tag of the command, in order.
+    ///
+    /// A well formed command has at most one.
+    pub fn all_request_ids(&self) -> Vec<u8> {
+        self.actions
+            .iter()
+            .filter_map(|action| match action {
+                Action::RequestTag(tag) => Some(tag.id),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Identifiers of every response tag of the command, in order.
+    pub fn all_response_ids(&self) -> Vec<u8> {
+        self.actions
+            .iter()
+            .filter_map(|action| match action {
+                Action::ResponseTag(tag) => Some(tag.id),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn is_last_response(&self) -> bool {
+        self.response_tag().map(|tag| tag.eop).unwrap_or(false)
     }
 }
+#[cfg(feature = "actions")]
 #[test]
 fn test_command() {
     let cmd = Command {
@@ -168,6 +1076,249 @@ fn test_command() {
         cmd,
     );
 }
+#[cfg(feature = "actions")]
+#[test]
+fn test_command_decode_limited() {
+    let data = hex!("00 00 00");
+    assert_eq!(
+        Command::decode_limited(&data, 3).unwrap(),
+        Command::decode(&data).unwrap()
+    );
+    assert_eq!(
+        Command::decode_limited(&data, 2),
+        Err(WithOffset {
+            offset: 2,
+            value: CommandParseFail {
+                actions: vec![Action::nop(false, false), Action::nop(false, false)],
+                error: action::ActionDecodingError::TooManyActions,
+            },
+        })
+    );
+}
+#[test]
+fn test_command_filter_op() {
+    let cmd = Command {
+        actions: vec![
+            Action::request_tag(true, 66),
+            Action::nop(false, true),
+            Action::read_file_data(false, true, 1, 0, 4),
+            Action::nop(false, false),
+        ],
+    };
+    assert_eq!(
+        cmd.filter_op(action::OpCode::Nop).collect::<Vec<_>>(),
+        vec![&Action::nop(false, true), &Action::nop(false, false)]
+    );
+    assert_eq!(cmd.count_op(action::OpCode::Nop), 2);
+    assert_eq!(cmd.count_op(action::OpCode::ReadFileData), 1);
+    assert_eq!(cmd.count_op(action::OpCode::Status), 0);
+}
+#[test]
+fn test_command_dedup_actions() {
+    let mut cmd = Command {
+        actions: vec![
+            Action::nop(false, true),
+            Action::nop(false, true),
+            Action::read_file_data(false, true, 1, 0, 8),
+            Action::nop(false, true),
+            Action::read_file_data(false, true, 1, 0, 8),
+            Action::read_file_data(false, true, 1, 0, 4),
+        ],
+    };
+    cmd.dedup_actions();
+    assert_eq!(
+        cmd.actions,
+        vec![
+            Action::nop(false, true),
+            Action::read_file_data(false, true, 1, 0, 8),
+            Action::nop(false, true),
+            Action::read_file_data(false, true, 1, 0, 8),
+            Action::read_file_data(false, true, 1, 0, 4),
+        ]
+    );
+}
+
+#[test]
+fn test_command_split_off() {
+    let mut cmd = Command {
+        actions: vec![
+            Action::request_tag(true, 1),
+            Action::test_flush_file(false, false, 2),
+            Action::read_file_data(false, true, 1, 0, 8),
+        ],
+    };
+    let tail = cmd.split_off(2);
+    assert_eq!(
+        cmd.actions,
+        vec![
+            Action::request_tag(true, 1),
+            Action::test_flush_file(false, false, 2),
+        ]
+    );
+    assert_eq!(
+        tail.actions,
+        vec![Action::read_file_data(false, true, 1, 0, 8)]
+    );
+    assert_eq!(cmd.split_off(2), Command::default());
+}
+
+#[test]
+fn test_command_eq_ignoring_tags() {
+    let cmd = Command {
+        actions: vec![
+            Action::request_tag(true, 1),
+            Action::read_file_data(false, true, 1, 0, 8),
+            Action::response_tag(true, false, 2),
+        ],
+    };
+    let mut other = Command {
+        actions: vec![
+            Action::request_tag(true, 42),
+            Action::read_file_data(false, true, 1, 0, 8),
+            Action::response_tag(true, false, 43),
+        ],
+    };
+    assert!(cmd.eq_ignoring_tags(&other));
+    assert_ne!(cmd, other);
+
+    other.actions[2] = Action::response_tag(true, true, 2);
+    assert!(!cmd.eq_ignoring_tags(&other));
+    other.actions[2] = Action::response_tag(true, false, 2);
+    other.actions[0] = Action::request_tag(false, 1);
+    assert!(!cmd.eq_ignoring_tags(&other));
+    other.actions[0] = Action::request_tag(true, 1);
+    other.actions[1] = Action::read_file_data(false, true, 1, 0, 4);
+    assert!(!cmd.eq_ignoring_tags(&other));
+    other.actions.pop();
+    assert!(!cmd.eq_ignoring_tags(&other));
+}
+
+#[test]
+fn test_command_canonicalize() {
+    let mut cmd = Command {
+        actions: vec![
+            Action::read_file_data(false, true, 4, 0, 1),
+            Action::read_file_data(false, true, 2, 8, 1),
+            Action::read_file_data(false, true, 2, 0, 1),
+            Action::ActionQuery(action::QueryAction::in_range(1, 0, 0, 1)),
+            // Conditioned by the query
+            Action::read_file_data(false, true, 3, 0, 1),
+            Action::read_file_data(false, true, 1, 0, 1),
+            Action::read_file_data(false, true, 0, 0, 1),
+            // Grouped
+            Action::read_file_data(true, true, 9, 0, 1),
+            Action::read_file_data(false, true, 8, 0, 1),
+            Action::read_file_data(false, true, 7, 0, 1),
+            Action::nop(true, true),
+            Action::nop(true, false),
+            Action::nop(false, false),
+        ],
+    };
+    cmd.canonicalize();
+    assert_eq!(
+        cmd.actions,
+        vec![
+            Action::read_file_data(false, true, 2, 0, 1),
+            Action::read_file_data(false, true, 2, 8, 1),
+            Action::read_file_data(false, true, 4, 0, 1),
+            Action::ActionQuery(action::QueryAction::in_range(1, 0, 0, 1)),
+            Action::read_file_data(false, true, 3, 0, 1),
+            Action::read_file_data(false, true, 0, 0, 1),
+            Action::read_file_data(false, true, 1, 0, 1),
+            Action::read_file_data(true, true, 9, 0, 1),
+            Action::read_file_data(false, true, 8, 0, 1),
+            Action::read_file_data(false, true, 7, 0, 1),
+            Action::nop(false, true),
+        ]
+    );
+
+    // Canonicalizing is idempotent
+    let expected = cmd.clone();
+    cmd.canonicalize();
+    assert_eq!(cmd, expected);
+}
+#[cfg(feature = "actions")]
+#[test]
+fn test_command_bytes_needed() {
+    let data = hex!("B4 42   41 00 00 08   81 04 02 03  C0");
+    let boundaries = [0, 2, 6, 10, 11];
+    for len in 0..=data.len() {
+        let needed = Command::bytes_needed(&data[..len]);
+        if boundaries.contains(&len) {
+            assert_eq!(needed, None, "{}", len);
+        } else {
+            let next = boundaries.iter().find(|b| **b > len).unwrap();
+            let needed = needed.unwrap();
+            assert!(needed >= 1 && len + needed <= *next, "{}", len);
+        }
+    }
+    assert_eq!(Command::bytes_needed(&data[..1]), Some(1));
+    assert_eq!(Command::bytes_needed(&data[..3]), Some(3));
+    // Unknown opcode
+    assert_eq!(Command::bytes_needed(&hex!("3E")), None);
+}
+#[cfg(feature = "actions")]
+#[test]
+fn test_command_read_from() {
+    let data = hex!("B4 42  C0");
+    assert_eq!(
+        Command::read_from(&data[..]).unwrap(),
+        Command::decode(&data).unwrap()
+    );
+    match Command::read_from(&hex!("B4")[..]) {
+        Err(DecodeError::Parse(e)) => assert_eq!(e.offset, 0),
+        e => panic!("unexpected result {:?}", e),
+    }
+
+    struct Failing;
+    impl std::io::Read for Failing {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("broken"))
+        }
+    }
+    match Command::read_from(Failing) {
+        Err(DecodeError::Io(e)) => assert_eq!(e.to_string(), "broken"),
+        e => panic!("unexpected result {:?}", e),
+    }
+}
+#[cfg(feature = "actions")]
+#[test]
+fn test_command_decode_ignoring_trailing_zeros() {
+    let cmd = Command {
+        actions: vec![Action::request_tag(true, 66), Action::nop(false, false)],
+    };
+    assert_eq!(
+        Command::decode_ignoring_trailing_zeros(&hex!("B4 42 00 C0 00 00 00")).unwrap(),
+        Command {
+            actions: vec![
+                Action::request_tag(true, 66),
+                Action::nop(false, false),
+                Action::nop(true, true),
+            ],
+        }
+    );
+    assert_eq!(
+        Command::decode_ignoring_trailing_zeros(&hex!("B4 42 00 40")).unwrap(),
+        Command {
+            actions: vec![
+                Action::request_tag(true, 66),
+                Action::nop(false, false),
+                Action::nop(false, true),
+            ],
+        }
+    );
+    // Padding is indistinguishable from trailing flagless Nops
+    assert_eq!(
+        Command::decode_ignoring_trailing_zeros(&cmd.encode()).unwrap(),
+        Command {
+            actions: vec![Action::request_tag(true, 66)],
+        }
+    );
+    assert_eq!(
+        Command::decode_ignoring_trailing_zeros(&hex!("00 00")).unwrap(),
+        Command::default()
+    );
+}
 #[test]
 fn test_command_display() {
     assert_eq!(
@@ -185,6 +1336,27 @@ fn test_command_display() {
     );
 }
 
+#[test]
+fn test_command_display_with_hex() {
+    let cmd = Command {
+        actions: vec![Action::request_tag(true, 66), Action::nop(true, true)],
+    };
+    assert_eq!(
+        DisplayWithHex(&cmd).to_string(),
+        "[RTAG[E](66); NOP[GR]]  (B4 42 C0)"
+    );
+    assert_eq!(DisplayWithHex(&Command::default()).to_string(), "[]  ()");
+}
+
+#[test]
+fn test_empty_command() {
+    let cmd = Command::default();
+    assert_eq!(cmd.to_string(), "[]");
+    assert_eq!(cmd.encoded_size(), 0);
+    assert!(cmd.encode().is_empty());
+    assert_eq!(Command::decode(&[]), Ok(cmd));
+}
+
 #[test]
 fn test_command_request_id() {
     assert_eq!(
@@ -210,6 +1382,22 @@ fn test_command_request_id() {
     );
 }
 
+#[test]
+fn test_command_all_ids() {
+    let cmd = Command {
+        actions: vec![
+            Action::request_tag(false, 44),
+            Action::nop(true, false),
+            Action::response_tag(true, false, 66),
+            Action::request_tag(true, 45),
+        ],
+    };
+    assert_eq!(cmd.request_id(), Some(44));
+    assert_eq!(cmd.all_request_ids(), vec![44, 45]);
+    assert_eq!(cmd.all_response_ids(), vec![66]);
+    assert!(Command::default().all_request_ids().is_empty());
+}
+
 #[test]
 fn test_comman_response_id() {
     assert_eq!(
@@ -241,6 +1429,538 @@ fn test_comman_response_id() {
     );
 }
 
+#[test]
+fn test_command_chunk_for_mtu() {
+    let cmd = Command {
+        actions: vec![
+            Action::request_tag(true, 1),
+            Action::read_file_data(false, true, 1, 0, 8),
+            Action::write_file_data(false, false, 2, 0, &hex!("0102030405")[..]),
+            Action::nop(false, true),
+        ],
+    };
+    // 2 + 4 + 9 + 1 bytes
+    assert_eq!(cmd.encoded_size(), 16);
+    assert!(cmd.fits_in(16));
+    assert!(!cmd.fits_in(15));
+    assert_eq!(cmd.chunk_for_mtu(16), Ok(vec![cmd.clone()]));
+
+    let chunks = cmd.chunk_for_mtu(10).unwrap();
+    assert_eq!(
+        chunks,
+        vec![
+            Command {
+                actions: vec![
+                    Action::chunk(action::Chunk::Start),
+                    Action::request_tag(true, 1),
+                    Action::read_file_data(false, true, 1, 0, 8),
+                ]
+            },
+            Command {
+                actions: vec![
+                    Action::chunk(action::Chunk::Continue),
+                    Action::write_file_data(false, false, 2, 0, &hex!("0102030405")[..]),
+                ]
+            },
+            Command {
+                actions: vec![Action::chunk(action::Chunk::End), Action::nop(false, true),]
+            },
+        ]
+    );
+    assert!(chunks.iter().all(|chunk| chunk.fits_in(10)));
+
+    assert_eq!(
+        cmd.chunk_for_mtu(9),
+        Err(ChunkingError::ActionTooBig { index: 2, size: 9 })
+    );
+}
+
+#[test]
+fn test_command_hex() {
+    let cmd = Command {
+        actions: vec![
+            Action::request_tag(true, 0x42),
+            Action::read_file_data(false, true, 0, 0, 8),
+            Action::nop(true, true),
+        ],
+    };
+    assert_eq!(cmd.to_hex(), "B44241000008C0");
+    assert_eq!(cmd.encode_hex_spaced(), "B4 42  41 00 00 08  C0");
+    assert_eq!(Command::default().encode_hex_spaced(), "");
+}
+
+#[test]
+fn test_command_from_actions() {
+    let actions = vec![
+        Action::request_tag(true, 0x42),
+        Action::read_file_data(false, true, 0, 0, 8),
+    ];
+    let cmd = Command {
+        actions: actions.clone(),
+    };
+    assert_eq!(Command::from(actions.clone()), cmd);
+    assert_eq!(Command::from(&actions[..]), cmd);
+    assert_eq!(actions.iter().cloned().collect::<Command>(), cmd);
+
+    let mut built = Command::default();
+    built.push(Action::request_tag(true, 0x42));
+    built.extend(actions[1..].iter().cloned());
+    assert_eq!(built, cmd);
+}
+
+#[test]
+fn test_action_byte_ranges() {
+    let mut cmd = Command {
+        actions: vec![
+            Action::request_tag(true, 0x42),
+            Action::read_file_data(false, true, 0, 0, 8),
+            Action::nop(true, true),
+        ],
+    };
+    let ranges = cmd.action_byte_ranges();
+    assert_eq!(ranges, vec![0..2, 2..6, 6..7]);
+    assert_eq!(Command::default().action_byte_ranges(), vec![]);
+
+    // Splice a same sized action in the encoded command
+    let mut data = cmd.encode().into_vec();
+    let replacement = Action::read_file_data(false, true, 1, 4, 4);
+    data[ranges[1].clone()].copy_from_slice(&replacement.encode());
+    cmd.actions[1] = replacement;
+    assert_eq!(&data[..], &cmd.encode()[..]);
+}
+
+#[cfg(feature = "actions")]
+#[test]
+fn test_decode_events() {
+    let data = hex!("B4 42   41 00 00 08   C0");
+    let mut actions = vec![];
+    assert_eq!(
+        decode_events(&data, |action| {
+            actions.push(action);
+            ControlFlow::Continue(())
+        }),
+        Ok(data.len())
+    );
+    assert_eq!(actions, Command::decode(&data).unwrap().actions);
+
+    let mut count = 0;
+    assert_eq!(
+        decode_events(&data, |_| {
+            count += 1;
+            if count == 2 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        }),
+        Ok(6)
+    );
+    assert_eq!(count, 2);
+
+    assert_eq!(
+        decode_events(&data[..4], |_| ControlFlow::Continue(())),
+        Err(WithOffset::new(
+            2,
+            action::ActionDecodingError::ReadFileData(crate::codec::StdError::MissingBytes(2))
+        ))
+    );
+}
+
+#[test]
+fn test_check_security() {
+    let d7_conf = |nls_method| dash7::InterfaceConfiguration {
+        qos: dash7::Qos {
+            retry: dash7::RetryMode::No,
+            resp: dash7::RespMode::Any,
+        },
+        to: dash7::CompressedTime(0x23),
+        te: dash7::CompressedTime(0x34),
+        nls_method,
+        access_class: dash7::AccessClass(0xFF),
+        address: dash7::Address::Vid([0xAB, 0xCD]),
+        use_vid: false,
+        group_condition: dash7::GroupCondition::Any,
+    };
+    let overloaded = |nls_method| {
+        Action::IndirectForward(action::IndirectForward {
+            resp: true,
+            interface: operand::IndirectInterface::Overloaded(
+                operand::OverloadedIndirectInterface {
+                    interface_file_id: 4,
+                    nls_method,
+                    access_class: 0xFF,
+                    address: dash7::Address::NbId(0x15),
+                },
+            ),
+        })
+    };
+    let mut cmd = Command {
+        actions: vec![
+            Action::request_tag(true, 1),
+            Action::Forward(action::Forward {
+                resp: false,
+                conf: operand::InterfaceConfiguration::Host,
+            }),
+            Action::Forward(action::Forward {
+                resp: true,
+                conf: operand::InterfaceConfiguration::D7asp(d7_conf(dash7::NlsMethod::AesCcm32)),
+            }),
+            overloaded(dash7::NlsMethod::AesCcm32),
+            Action::read_file_data(false, true, 0, 0, 8),
+        ],
+    };
+    assert_eq!(cmd.check_security(dash7::NlsMethod::AesCcm32), Ok(()));
+    // A stronger method than the required one is accepted
+    assert_eq!(cmd.check_security(dash7::NlsMethod::AesCbcMac32), Ok(()));
+    assert_eq!(cmd.check_security(dash7::NlsMethod::AesCtr), Ok(()));
+    assert_eq!(
+        cmd.check_security(dash7::NlsMethod::AesCcm64),
+        Err(SecurityError::NlsMethod {
+            index: 2,
+            actual: dash7::NlsMethod::AesCcm32,
+        })
+    );
+
+    cmd.actions[3] = overloaded(dash7::NlsMethod::None);
+    assert_eq!(
+        cmd.check_security(dash7::NlsMethod::AesCcm32),
+        Err(SecurityError::NlsMethod {
+            index: 3,
+            actual: dash7::NlsMethod::None,
+        })
+    );
+    assert_eq!(cmd.check_security(dash7::NlsMethod::None), Ok(()));
+
+    cmd.actions[3] = Action::IndirectForward(action::IndirectForward {
+        resp: true,
+        interface: operand::IndirectInterface::NonOverloaded(
+            operand::NonOverloadedIndirectInterface {
+                interface_file_id: 4,
+            },
+        ),
+    });
+    assert_eq!(
+        cmd.check_security(dash7::NlsMethod::AesCcm32),
+        Err(SecurityError::UncheckedInterface {
+            index: 3,
+            interface_file_id: 4,
+        })
+    );
+}
+
+#[test]
+fn test_validate() {
+    let mut cmd = Command {
+        actions: vec![
+            Action::chunk(action::Chunk::Start),
+            Action::read_file_data(false, true, 0, 0, 8),
+        ],
+    };
+    assert_eq!(cmd.validate(), Ok(()));
+
+    cmd.actions[1] = Action::read_file_data(false, true, 0, 0, varint::MAX + 1);
+    assert_eq!(
+        cmd.validate(),
+        Err(CommandValidationError::Operand {
+            index: 1,
+            error: action::OperandValidationError::SizeTooBig,
+        })
+    );
+
+    cmd.actions[1] = Action::ActionQuery(action::QueryAction::in_range(1, 0, 8, 2));
+    assert_eq!(
+        cmd.validate(),
+        Err(CommandValidationError::Query {
+            index: 1,
+            error: operand::QueryValidationError::StartGreaterThanStop,
+        })
+    );
+
+    cmd.actions.swap(0, 1);
+    cmd.actions[0] = Action::nop(false, false);
+    assert_eq!(
+        cmd.validate(),
+        Err(CommandValidationError::MisplacedChunk { index: 1 })
+    );
+
+    let cmd = Command {
+        actions: vec![
+            Action::request_tag(false, 1),
+            Action::response_tag(false, false, 2),
+            Action::response_tag(true, false, 3),
+            Action::request_tag(true, 4),
+        ],
+    };
+    assert_eq!(
+        cmd.validate(),
+        Err(CommandValidationError::MultipleRequestTags { index: 3 })
+    );
+}
+
+#[test]
+fn test_actions_mut() {
+    let mut cmd = Command {
+        actions: vec![Action::nop(false, false)],
+    };
+    cmd.actions_mut()
+        .push(Action::read_file_data(false, true, 0, 0, 8));
+    assert_eq!(cmd.actions.len(), 2);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "invalid command after edition")]
+fn test_actions_mut_invalid() {
+    let mut cmd = Command {
+        actions: vec![Action::nop(false, false)],
+    };
+    cmd.actions_mut().push(Action::chunk(action::Chunk::Start));
+}
+
+#[test]
+fn test_expected_responses() {
+    let cmd = Command {
+        actions: vec![
+            Action::request_tag(true, 0x42),
+            Action::read_file_data(false, true, 0, 0, 8),
+            Action::nop(false, false),
+            Action::write_file_data(false, true, 1, 0, &[0u8][..]),
+        ],
+    };
+    assert_eq!(
+        cmd.expected_responses(),
+        vec![
+            (0, action::OpCode::ResponseTag),
+            (1, action::OpCode::ReturnFileData),
+            (3, action::OpCode::Status),
+        ]
+    );
+    assert_eq!(Command::default().expected_responses(), vec![]);
+}
+
+#[test]
+fn test_peek_header() {
+    assert_eq!(
+        Command::peek_header(&hex!("B4 42   41 00 00 08")),
+        Ok((Some(0x42), action::OpCode::ReadFileData))
+    );
+    // Only the opcode of the action is decoded
+    assert_eq!(
+        Command::peek_header(&hex!("B4 42   41")),
+        Ok((Some(0x42), action::OpCode::ReadFileData))
+    );
+    assert_eq!(
+        Command::peek_header(&hex!("41 00 00 08")),
+        Ok((None, action::OpCode::ReadFileData))
+    );
+    assert_eq!(
+        Command::peek_header(&hex!("B4")),
+        Err(WithOffset::new_head(
+            action::ActionDecodingError::RequestTag(crate::codec::StdError::MissingBytes(1))
+        ))
+    );
+    assert_eq!(
+        Command::peek_header(&hex!("B4 42")),
+        Err(WithOffset::new(2, action::ActionDecodingError::NoData))
+    );
+    assert_eq!(
+        Command::peek_header(&hex!("B4 42   05")),
+        Err(WithOffset::new(
+            2,
+            action::ActionDecodingError::UnknownOpCode(5)
+        ))
+    );
+}
+
+#[cfg(feature = "actions")]
+#[test]
+fn test_decode_with_overrides() {
+    let data = hex!("B4 42   7A 00 00 08");
+    assert_eq!(
+        Command::decode(&data),
+        Err(WithOffset::new(
+            2,
+            CommandParseFail {
+                actions: vec![Action::request_tag(true, 0x42)],
+                error: action::ActionDecodingError::UnknownOpCode(0x3A),
+            }
+        ))
+    );
+    let overrides = action::OpCodeMap::new().with(0x3A, action::OpCode::ReadFileData);
+    let cmd = Command::decode_with_overrides(&data, &overrides).unwrap();
+    assert_eq!(
+        cmd.actions,
+        vec![
+            Action::request_tag(true, 0x42),
+            Action::read_file_data(false, true, 0, 0, 8),
+        ]
+    );
+    // Re-encoded with the standard opcode
+    assert_eq!(&cmd.encode()[..], &hex!("B4 42   41 00 00 08"));
+    assert_eq!(overrides.get(0x3A), Some(action::OpCode::ReadFileData));
+    assert_eq!(overrides.get(0x01), None);
+}
+
+#[test]
+fn test_encode_in_uninit() {
+    let cmd = Command {
+        actions: vec![
+            Action::request_tag(true, 0x42),
+            Action::read_file_data(false, true, 0, 0, 8),
+        ],
+    };
+    // Stale content must not leak in the flags of the actions
+    let mut buffer = [std::mem::MaybeUninit::new(0xFF); 16];
+    let size = cmd.encode_in_uninit(&mut buffer);
+    assert_eq!(size, 6);
+    let encoded: Vec<u8> = buffer[..size]
+        .iter()
+        .map(|byte| unsafe { byte.assume_init() })
+        .collect();
+    assert_eq!(&encoded[..], &hex!("B4 42   41 00 00 08"));
+}
+
+#[cfg(feature = "actions")]
+#[test]
+fn test_decode_exact() {
+    assert_eq!(
+        Command::decode_exact(&hex!("B4 42   41 00 00 08")).unwrap(),
+        Command::decode(&hex!("B4 42   41 00 00 08")).unwrap()
+    );
+    match Command::decode_exact(&hex!("B4 42   41 00 00")) {
+        Err(DecodeError::TrailingBytes(3)) => (),
+        r => panic!("{:?}", r),
+    }
+    match Command::decode_exact(&hex!("B4 42   05")) {
+        Err(DecodeError::Parse(e)) => assert_eq!(e.offset, 2),
+        r => panic!("{:?}", r),
+    }
+    // Non minimal varint offset: the read takes one more byte than its encoded size
+    match Command::decode_exact(&hex!("41 00 4000 08   41 00")) {
+        Err(DecodeError::TrailingBytes(2)) => (),
+        r => panic!("{:?}", r),
+    }
+}
+
+#[cfg(feature = "actions")]
+#[test]
+fn test_decode_annotated() {
+    // Non minimal varint offset in the read: the following action starts one byte later than
+    // its encoded size tells.
+    let data = hex!("B4 42   41 00 4000 08   C0");
+    assert_eq!(
+        Command::decode_annotated(&data).unwrap(),
+        vec![
+            (0, Action::request_tag(true, 0x42)),
+            (2, Action::read_file_data(false, true, 0, 0, 8)),
+            (7, Action::nop(true, true)),
+        ]
+    );
+    assert_eq!(
+        Command::decode_annotated(&data[..6]).unwrap_err(),
+        Command::decode(&data[..6]).unwrap_err()
+    );
+}
+
+#[cfg(feature = "actions")]
+#[test]
+fn test_check_roundtrip() {
+    assert_eq!(check_roundtrip(&hex!("B4 42   41 00 00 08")), Ok(()));
+    // Non minimal varint offset: decodes, but is re-encoded on a single byte
+    let mismatch = check_roundtrip(&hex!("41 00 4000 08")).unwrap_err();
+    assert_eq!(
+        mismatch,
+        RoundtripMismatch::Byte {
+            offset: 2,
+            input: Some(0x40),
+            encoded: Some(0x00),
+        }
+    );
+    assert_eq!(
+        mismatch.to_string(),
+        "at offset 2: input 0x40, encoded 0x00"
+    );
+    assert!(matches!(
+        check_roundtrip(&hex!("41 00")),
+        Err(RoundtripMismatch::Decode(_))
+    ));
+}
+
+#[test]
+fn test_response_skeleton() {
+    let request = Command {
+        actions: vec![
+            Action::request_tag(true, 44),
+            Action::read_file_data(false, true, 0, 0, 8),
+        ],
+    };
+    assert_eq!(
+        request.response_skeleton(),
+        Command {
+            actions: vec![Action::response_tag(true, false, 44)],
+        }
+    );
+    assert_eq!(
+        Command {
+            actions: vec![Action::read_file_data(false, true, 0, 0, 8)],
+        }
+        .response_skeleton(),
+        Command::default()
+    );
+}
+
+#[cfg(feature = "actions")]
+#[test]
+fn test_interface_status() {
+    let response = Command::decode(&hex!(
+        "A3 08   62 D7 16  01 0123 4E 5A 0C 05 06 07 0800 0900  37 FF ABCD  0011223344   20 00 00 01 FF"
+    ))
+    .unwrap();
+    let status = match response.interface_status() {
+        Some(operand::InterfaceStatus::D7asp(status)) => status,
+        other => panic!("{:?}", other),
+    };
+    assert_eq!(status.rssi_dbm(), -78);
+    assert_eq!(status.link_budget_db(), 90);
+    assert_eq!(status.snr_db(), 12);
+    assert_eq!(
+        Command {
+            actions: vec![Action::response_tag(true, false, 8)],
+        }
+        .interface_status(),
+        None
+    );
+}
+
+#[test]
+fn test_command_tags() {
+    let cmd = Command {
+        actions: vec![
+            Action::nop(true, false),
+            Action::request_tag(true, 44),
+            Action::response_tag(false, true, 66),
+            Action::request_tag(false, 45),
+        ],
+    };
+    assert_eq!(
+        cmd.request_tag(),
+        Some(&action::RequestTag { eop: true, id: 44 })
+    );
+    assert_eq!(
+        cmd.response_tag(),
+        Some(&action::ResponseTag {
+            eop: false,
+            err: true,
+            id: 66
+        })
+    );
+    let cmd = Command {
+        actions: vec![Action::nop(true, false)],
+    };
+    assert_eq!(cmd.request_tag(), None);
+    assert_eq!(cmd.response_tag(), None);
+}
+
 #[test]
 fn test_command_is_last_response() {
     assert!(Command {
@@ -269,3 +1989,58 @@ fn test_command_is_last_response() {
     }
     .is_last_response());
 }
+
+#[test]
+fn test_command_coalesce_reads() {
+    let mut cmd = Command {
+        actions: vec![
+            Action::read_file_data(false, true, 1, 0, 4),
+            Action::read_file_data(false, true, 1, 4, 4),
+            Action::read_file_data(false, true, 1, 8, 2),
+            // Different file
+            Action::read_file_data(false, true, 2, 10, 2),
+            // Different flags
+            Action::read_file_data(false, false, 2, 12, 2),
+            // Not contiguous
+            Action::read_file_data(false, false, 2, 15, 2),
+            // Separated by another action
+            Action::nop(false, false),
+            Action::read_file_data(false, false, 2, 17, 2),
+        ],
+    };
+    cmd.coalesce_reads();
+    assert_eq!(
+        cmd.actions,
+        vec![
+            Action::read_file_data(false, true, 1, 0, 10),
+            Action::read_file_data(false, true, 2, 10, 2),
+            Action::read_file_data(false, false, 2, 12, 2),
+            Action::read_file_data(false, false, 2, 15, 2),
+            Action::nop(false, false),
+            Action::read_file_data(false, false, 2, 17, 2),
+        ]
+    );
+
+    // The merged size must stay encodable
+    let mut cmd = Command {
+        actions: vec![
+            Action::read_file_data(false, true, 1, 0, varint::MAX),
+            Action::read_file_data(false, true, 1, varint::MAX, 1),
+        ],
+    };
+    let expected = cmd.clone();
+    cmd.coalesce_reads();
+    assert_eq!(cmd, expected);
+}
+
+#[cfg(feature = "actions")]
+#[test]
+fn test_bytes_conversions() {
+    use std::convert::TryFrom;
+    let data = hex!("B4 42   41 00 00 08");
+    let cmd = Command::try_from(&data[..]).unwrap();
+    assert_eq!(cmd, Command::decode(&data).unwrap());
+    let bytes: Vec<u8> = cmd.into();
+    assert_eq!(bytes, data.to_vec());
+    assert_eq!(Command::try_from(&data[..3]), Command::decode(&data[..3]));
+}
@@ -6,6 +6,7 @@ use hex_literal::hex;
 use std::convert::TryInto;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StatusCode {
     Received = 1,
     Ok = 0,
@@ -80,6 +81,7 @@ impl StatusCode {
 
 /// Result of an action in a previously sent request
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ActionStatus {
     /// Index of the ALP action associated with this status, in the original request as seen from
     /// the receiver side.
@@ -100,6 +102,16 @@ pub enum ActionStatusDecodingError {
     MissingBytes(usize),
     UnknownStatusCode(u8),
 }
+impl ActionStatusDecodingError {
+    /// Minimum number of bytes missing to decode the item, if the error is caused by a
+    /// truncated input.
+    pub fn missing_bytes(&self) -> Option<usize> {
+        match self {
+            Self::MissingBytes(n) => Some(*n),
+            Self::UnknownStatusCode(_) => None,
+        }
+    }
+}
 impl Codec for ActionStatus {
     type Error = ActionStatusDecodingError;
     fn encoded_size(&self) -> usize {
@@ -9,6 +9,7 @@ use hex_literal::hex;
 
 /// Describe the location of some data on the filesystem (file + data offset).
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FileOffset {
     pub id: u8,
     pub offset: u32,
@@ -24,6 +25,16 @@ pub enum FileOffsetDecodingError {
     MissingBytes(usize),
     Offset(StdError),
 }
+impl FileOffsetDecodingError {
+    /// Minimum number of bytes missing to decode the item, if the error is caused by a
+    /// truncated input.
+    pub fn missing_bytes(&self) -> Option<usize> {
+        match self {
+            Self::MissingBytes(n) => Some(*n),
+            Self::Offset(e) => e.missing_bytes(),
+        }
+    }
+}
 impl Codec for FileOffset {
     type Error = FileOffsetDecodingError;
     fn encoded_size(&self) -> usize {
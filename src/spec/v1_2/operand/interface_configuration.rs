@@ -6,8 +6,10 @@ use crate::{
 };
 #[cfg(test)]
 use hex_literal::hex;
+use std::convert::TryFrom;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum InterfaceId {
     Host = 0,
@@ -31,18 +33,182 @@ impl std::convert::TryFrom<u8> for InterfaceId {
         }
     }
 }
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum InterfaceIdDecodingError {
+    MissingBytes(usize),
+    BadInterfaceId(u8),
+}
+impl InterfaceIdDecodingError {
+    /// Minimum number of bytes missing to decode the item, if the error is caused by a
+    /// truncated input.
+    pub fn missing_bytes(&self) -> Option<usize> {
+        match self {
+            Self::MissingBytes(n) => Some(*n),
+            Self::BadInterfaceId(_) => None,
+        }
+    }
+}
+impl std::fmt::Display for InterfaceIdDecodingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::MissingBytes(n) => write!(f, "missing {} bytes", n),
+            Self::BadInterfaceId(id) => write!(f, "bad interface id 0x{:02X}", id),
+        }
+    }
+}
+impl Codec for InterfaceId {
+    type Error = InterfaceIdDecodingError;
+    fn encoded_size(&self) -> usize {
+        1
+    }
+    unsafe fn encode_in(&self, out: &mut [u8]) -> usize {
+        out[0] = *self as u8;
+        1
+    }
+    fn decode(out: &[u8]) -> Result<WithSize<Self>, WithOffset<Self::Error>> {
+        if out.is_empty() {
+            return Err(WithOffset::new_head(Self::Error::MissingBytes(1)));
+        }
+        let value = Self::try_from(out[0])
+            .map_err(|id| WithOffset::new_head(Self::Error::BadInterfaceId(id)))?;
+        Ok(WithSize { value, size: 1 })
+    }
+}
+#[test]
+fn test_interface_id() {
+    test_item(InterfaceId::Host, &hex!("00"));
+    test_item(InterfaceId::D7asp, &hex!("D7"));
+    assert_eq!(InterfaceId::D7asp.to_string(), "D7");
+    assert_eq!(
+        InterfaceId::decode(&hex!("42")),
+        Err(WithOffset::new_head(
+            InterfaceIdDecodingError::BadInterfaceId(0x42)
+        ))
+    );
+    assert_eq!(
+        InterfaceIdDecodingError::BadInterfaceId(0x42).to_string(),
+        "bad interface id 0x42"
+    );
+    assert_eq!(
+        InterfaceId::decode(&[]),
+        Err(WithOffset::new_head(
+            InterfaceIdDecodingError::MissingBytes(1)
+        ))
+    );
+}
+
+/// Configuration of an interface unknown to this library (see `register_custom_interface`,
+/// with the `dynamic_interfaces` feature).
+///
+/// The implementations must be `Send` and `Sync` so that the commands holding them can be
+/// shared between threads like any other command.
+pub trait CustomInterface: std::fmt::Debug + std::fmt::Display + Send + Sync {
+    /// Interface id, encoded before the configuration
+    fn interface_id(&self) -> u8;
+    /// Size of the encoded configuration, without the interface id
+    fn encoded_size(&self) -> usize;
+    /// Encodes the configuration, without the interface id.
+    /// # Safety
+    /// `out` must be at least [encoded_size](#tymethod.encoded_size) bytes long.
+    unsafe fn encode_in(&self, out: &mut [u8]) -> usize;
+    fn clone_box(&self) -> Box<dyn CustomInterface>;
+    /// Downcasting support, to get the configuration back as its concrete type.
+    fn as_any(&self) -> &dyn std::any::Any;
+}
+impl Clone for Box<dyn CustomInterface> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+/// Two custom configurations are equal if they encode the same way.
+impl PartialEq for dyn CustomInterface {
+    fn eq(&self, other: &Self) -> bool {
+        let encode = |conf: &dyn CustomInterface| {
+            let mut data = vec![0; conf.encoded_size()];
+            unsafe { conf.encode_in(&mut data) };
+            data
+        };
+        self.interface_id() == other.interface_id() && encode(self) == encode(other)
+    }
+}
+
+/// Result of the decoding of a custom interface configuration
+#[cfg(feature = "dynamic_interfaces")]
+pub type CustomInterfaceDecoding =
+    Result<WithSize<Box<dyn CustomInterface>>, WithOffset<InterfaceConfigurationDecodingError>>;
+
+/// Decoder of a custom interface configuration, given the bytes following its interface id.
+///
+/// The error offsets are relative to the given bytes.
+#[cfg(feature = "dynamic_interfaces")]
+pub type CustomInterfaceDecoder = fn(&[u8]) -> CustomInterfaceDecoding;
+
+#[cfg(feature = "dynamic_interfaces")]
+static CUSTOM_INTERFACES: std::sync::RwLock<[Option<CustomInterfaceDecoder>; 256]> =
+    std::sync::RwLock::new([None; 256]);
+
+/// Registers the decoder of the configurations of the interface `id`, returning the decoder it
+/// replaces.
+///
+/// The registry is global to the process, and shared by all the dialects: the decoders can be
+/// registered from any thread, and are used by all the decodings started after their
+/// registration.
+/// # Panics
+/// Panics if `id` is the id of an interface supported by this library.
+#[cfg(feature = "dynamic_interfaces")]
+pub fn register_custom_interface(
+    id: u8,
+    decoder: CustomInterfaceDecoder,
+) -> Option<CustomInterfaceDecoder> {
+    assert!(
+        InterfaceId::try_from(id).is_err(),
+        "interface id 0x{:02X} is not a custom one",
+        id
+    );
+    let mut registry = CUSTOM_INTERFACES.write().unwrap_or_else(|e| e.into_inner());
+    registry[id as usize].replace(decoder)
+}
+
+/// Removes the decoder of the interface `id`, returning it.
+#[cfg(feature = "dynamic_interfaces")]
+pub fn unregister_custom_interface(id: u8) -> Option<CustomInterfaceDecoder> {
+    let mut registry = CUSTOM_INTERFACES.write().unwrap_or_else(|e| e.into_inner());
+    registry[id as usize].take()
+}
+
+/// Decodes the custom interface configuration at the head of `out` (interface id included), if
+/// a decoder is registered for its interface id.
+#[cfg(feature = "dynamic_interfaces")]
+pub(crate) fn decode_custom_interface(out: &[u8]) -> Option<CustomInterfaceDecoding> {
+    let id = *out.first()?;
+    let decoder = CUSTOM_INTERFACES.read().unwrap_or_else(|e| e.into_inner())[id as usize]?;
+    Some(
+        decoder(&out[1..])
+            .map(|WithSize { value, size }| WithSize {
+                value,
+                size: size + 1,
+            })
+            .map_err(|e| e.shift(1)),
+    )
+}
 
 /// Meta data required to send a packet depending on the sending interface type
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum InterfaceConfiguration {
     Host,
     D7asp(dash7::InterfaceConfiguration),
+    /// Configuration of a custom interface. Only the `dynamic_interfaces` feature decodes them,
+    /// through the registered decoders. It cannot be (de)serialized with serde.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(Box<dyn CustomInterface>),
 }
 impl std::fmt::Display for InterfaceConfiguration {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             Self::Host => write!(f, "HOST"),
             Self::D7asp(conf) => write!(f, "D7:{}", conf),
+            Self::Custom(conf) => write!(f, "0x{:02X}:{}", conf.interface_id(), conf),
         }
     }
 }
@@ -52,12 +218,24 @@ pub enum InterfaceConfigurationDecodingError {
     D7asp(dash7::InterfaceConfigurationDecodingError),
     BadInterfaceId(u8),
 }
+impl InterfaceConfigurationDecodingError {
+    /// Minimum number of bytes missing to decode the item, if the error is caused by a
+    /// truncated input.
+    pub fn missing_bytes(&self) -> Option<usize> {
+        match self {
+            Self::MissingBytes(n) => Some(*n),
+            Self::D7asp(e) => e.missing_bytes(),
+            Self::BadInterfaceId(_) => None,
+        }
+    }
+}
 impl Codec for InterfaceConfiguration {
     type Error = InterfaceConfigurationDecodingError;
     fn encoded_size(&self) -> usize {
         1 + match self {
             InterfaceConfiguration::Host => 0,
             InterfaceConfiguration::D7asp(v) => v.encoded_size(),
+            InterfaceConfiguration::Custom(v) => v.encoded_size(),
         }
     }
     unsafe fn encode_in(&self, out: &mut [u8]) -> usize {
@@ -70,20 +248,22 @@ impl Codec for InterfaceConfiguration {
                 out[0] = InterfaceId::D7asp as u8;
                 1 + v.encode_in(&mut out[1..])
             }
+            InterfaceConfiguration::Custom(v) => {
+                out[0] = v.interface_id();
+                1 + v.encode_in(&mut out[1..])
+            }
         }
     }
     fn decode(out: &[u8]) -> Result<WithSize<Self>, WithOffset<Self::Error>> {
         if out.is_empty() {
             return Err(WithOffset::new_head(Self::Error::MissingBytes(1)));
         }
-        const HOST: u8 = InterfaceId::Host as u8;
-        const D7ASP: u8 = InterfaceId::D7asp as u8;
-        Ok(match out[0] {
-            HOST => WithSize {
+        Ok(match InterfaceId::try_from(out[0]) {
+            Ok(InterfaceId::Host) => WithSize {
                 value: InterfaceConfiguration::Host,
                 size: 1,
             },
-            D7ASP => {
+            Ok(InterfaceId::D7asp) => {
                 let WithSize { value, size } = dash7::InterfaceConfiguration::decode(&out[1..])
                     .map_err(|e| e.map_value(InterfaceConfigurationDecodingError::D7asp))?;
                 WithSize {
@@ -91,11 +271,15 @@ impl Codec for InterfaceConfiguration {
                     size: size + 1,
                 }
             }
-            id => {
+            Err(id) => {
+                #[cfg(feature = "dynamic_interfaces")]
+                if let Some(result) = decode_custom_interface(out) {
+                    return result.map(|v| v.map_value(InterfaceConfiguration::Custom));
+                }
                 return Err(WithOffset {
                     value: Self::Error::BadInterfaceId(id),
                     offset: 0,
-                })
+                });
             }
         })
     }
@@ -108,10 +292,10 @@ fn test_interface_configuration_d7asp() {
                 retry: dash7::RetryMode::No,
                 resp: dash7::RespMode::Any,
             },
-            to: 0x23,
-            te: 0x34,
+            to: dash7::CompressedTime(0x23),
+            te: dash7::CompressedTime(0x34),
             nls_method: dash7::NlsMethod::AesCcm32,
-            access_class: 0xFF,
+            access_class: dash7::AccessClass(0xFF),
             address: dash7::Address::Vid([0xAB, 0xCD]),
             use_vid: false,
             group_condition: dash7::GroupCondition::Any,
@@ -123,3 +307,108 @@ fn test_interface_configuration_d7asp() {
 fn test_interface_configuration_host() {
     test_item(InterfaceConfiguration::Host, &hex!("00"))
 }
+#[cfg(all(test, feature = "dynamic_interfaces"))]
+mod test_custom {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Vendor {
+        channel: u16,
+    }
+    impl std::fmt::Display for Vendor {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "ch={}", self.channel)
+        }
+    }
+    impl CustomInterface for Vendor {
+        fn interface_id(&self) -> u8 {
+            0x42
+        }
+        fn encoded_size(&self) -> usize {
+            2
+        }
+        unsafe fn encode_in(&self, out: &mut [u8]) -> usize {
+            out[..2].copy_from_slice(&self.channel.to_be_bytes());
+            2
+        }
+        fn clone_box(&self) -> Box<dyn CustomInterface> {
+            Box::new(self.clone())
+        }
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+    }
+    fn decode_vendor(out: &[u8]) -> CustomInterfaceDecoding {
+        if out.len() < 2 {
+            return Err(WithOffset::new_head(
+                InterfaceConfigurationDecodingError::MissingBytes(2 - out.len()),
+            ));
+        }
+        Ok(WithSize {
+            value: Box::new(Vendor {
+                channel: u16::from_be_bytes([out[0], out[1]]),
+            }),
+            size: 2,
+        })
+    }
+
+    #[test]
+    fn test_custom_interface_security() {
+        use crate::spec::v1_2::{action, Action, Command, SecurityError};
+        let cmd = Command {
+            actions: vec![Action::Forward(action::Forward {
+                resp: false,
+                conf: InterfaceConfiguration::Custom(Box::new(Vendor { channel: 1 })),
+            })],
+        };
+        assert_eq!(
+            cmd.check_security(dash7::NlsMethod::AesCcm32),
+            Err(SecurityError::CustomInterface {
+                index: 0,
+                interface_id: 0x42
+            })
+        );
+        assert_eq!(cmd.check_security(dash7::NlsMethod::None), Ok(()));
+    }
+
+    #[test]
+    fn test_custom_interface() {
+        assert_eq!(
+            InterfaceConfiguration::decode(&hex!("42 0102")),
+            Err(WithOffset::new_head(
+                InterfaceConfigurationDecodingError::BadInterfaceId(0x42)
+            ))
+        );
+        assert!(register_custom_interface(0x42, decode_vendor).is_none());
+
+        let conf = InterfaceConfiguration::Custom(Box::new(Vendor { channel: 0x0102 }));
+        test_item(conf.clone(), &hex!("42 0102"));
+        assert_eq!(conf.to_string(), "0x42:ch=258");
+        assert_eq!(
+            InterfaceConfiguration::decode(&hex!("42 01")),
+            Err(WithOffset::new(
+                1,
+                InterfaceConfigurationDecodingError::MissingBytes(1)
+            ))
+        );
+        match InterfaceConfiguration::decode(&hex!("42 0102"))
+            .unwrap()
+            .value
+        {
+            InterfaceConfiguration::Custom(conf) => assert_eq!(
+                conf.as_any().downcast_ref::<Vendor>(),
+                Some(&Vendor { channel: 0x0102 })
+            ),
+            conf => panic!("{:?}", conf),
+        }
+
+        assert!(unregister_custom_interface(0x42).is_some());
+        assert!(InterfaceConfiguration::decode(&hex!("42 0102")).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_register_builtin_interface() {
+        register_custom_interface(InterfaceId::D7asp as u8, decode_vendor);
+    }
+}
@@ -6,8 +6,10 @@ use crate::{
 };
 #[cfg(test)]
 use hex_literal::hex;
+use std::convert::TryFrom;
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InterfaceStatusUnknown {
     pub id: u8,
     pub data: Box<[u8]>,
@@ -21,6 +23,7 @@ impl std::fmt::Display for InterfaceStatusUnknown {
 // We should support the parsing and the encoding of this padding
 /// Meta data from a received packet depending on the receiving interface type
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum InterfaceStatus {
     Host,
     D7asp(dash7::InterfaceStatus),
@@ -40,6 +43,16 @@ pub enum InterfaceStatusDecodingError {
     MissingBytes(usize),
     BadInterfaceId(u8),
 }
+impl InterfaceStatusDecodingError {
+    /// Minimum number of bytes missing to decode the item, if the error is caused by a
+    /// truncated input.
+    pub fn missing_bytes(&self) -> Option<usize> {
+        match self {
+            Self::MissingBytes(n) => Some(*n),
+            Self::BadInterfaceId(_) => None,
+        }
+    }
+}
 impl From<StdError> for InterfaceStatusDecodingError {
     fn from(e: StdError) -> Self {
         match e {
@@ -87,17 +100,23 @@ impl Codec for InterfaceStatus {
         if out.is_empty() {
             return Err(WithOffset::new_head(Self::Error::MissingBytes(1)));
         }
-        const HOST: u8 = InterfaceId::Host as u8;
-        const D7ASP: u8 = InterfaceId::D7asp as u8;
         let mut offset = 1;
-        let value = match out[0] {
-            HOST => {
+        let value = match InterfaceId::try_from(out[0]) {
+            Ok(InterfaceId::Host) => {
+                if out.len() < 2 {
+                    return Err(WithOffset::new_head(Self::Error::MissingBytes(
+                        2 - out.len(),
+                    )));
+                }
                 offset += 1;
                 InterfaceStatus::Host
             }
-            D7ASP => {
+            Ok(InterfaceId::D7asp) => {
+                // The announced size is redundant with the structure of the status, and some
+                // senders get it wrong: the status is decoded by its own structure.
+                #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
                 let WithSize {
-                    value: size,
+                    value: announced_size,
                     size: size_size,
                 } = varint::decode(&out[offset..]).map_err(|e| {
                     let WithOffset { offset: off, value } = e;
@@ -106,22 +125,27 @@ impl Codec for InterfaceStatus {
                         value: value.into(),
                     }
                 })?;
-                let announced_size = size as usize;
                 offset += size_size;
-                let WithSize { value, size } = dash7::InterfaceStatus::decode(
-                    &out[offset..offset + announced_size],
-                )
-                .map_err(|e| {
-                    let WithOffset { offset: off, value } = e;
-                    WithOffset {
-                        offset: offset + off,
-                        value: value.into(),
-                    }
-                })?;
-                offset += size.max(announced_size);
+                let WithSize { value, size } = dash7::InterfaceStatus::decode(&out[offset..])
+                    .map_err(|e| {
+                        let WithOffset { offset: off, value } = e;
+                        WithOffset {
+                            offset: offset + off,
+                            value: value.into(),
+                        }
+                    })?;
+                #[cfg(feature = "tracing")]
+                if size != announced_size as usize {
+                    tracing::warn!(
+                        announced_size,
+                        size,
+                        "dash7 interface status length field mismatch"
+                    );
+                }
+                offset += size;
                 InterfaceStatus::D7asp(value)
             }
-            id => {
+            Err(id) => {
                 let WithSize {
                     value: size,
                     size: size_size,
@@ -166,7 +190,7 @@ fn test_interface_status_d7asp() {
             seq: 7,
             resp_to: 8,
             fof: 9,
-            access_class: 0xFF,
+            access_class: dash7::AccessClass(0xFF),
             address: dash7::Address::Vid([0xAB, 0xCD]),
             nls_state: dash7::NlsState::AesCcm32(hex!("00 11 22 33 44")),
         }),
@@ -174,6 +198,23 @@ fn test_interface_status_d7asp() {
     )
 }
 #[test]
+fn test_interface_status_d7asp_wrong_length() {
+    let status = hex!("01 0123 02 03 04 B0 06 07 0800 0900   37 FF ABCD  0011223344");
+    let expected = InterfaceStatus::decode(&[&hex!("D7 16")[..], &status[..]].concat())
+        .unwrap()
+        .value;
+    for announced in [0x00, 0x10, 0x20] {
+        let data = [&[0xD7, announced][..], &status[..], &hex!("FF")[..]].concat();
+        assert_eq!(
+            InterfaceStatus::decode(&data),
+            Ok(WithSize {
+                value: expected.clone(),
+                size: 2 + status.len(),
+            })
+        );
+    }
+}
+#[test]
 fn test_interface_status_host() {
     test_item(InterfaceStatus::Host, &hex!("00 00"))
 }
@@ -1,15 +1,46 @@
-use crate::codec::{Codec, WithOffset, WithSize};
+use crate::{
+    codec::{Codec, StdError, WithOffset, WithSize},
+    spec::v1_2::varint,
+};
 
 // ALP SPEC: where is this defined? Link? Not found in either specs !
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Permission {
     Dash7([u8; 8]),
+    /// Permission of an unknown kind, encoded as its id followed by the varint length of its
+    /// payload and the payload.
+    ///
+    /// The specification defines no other kind, hence no way to delimit one: this framing is
+    /// only meant to let such permissions round-trip without swallowing the following actions.
+    Rfu(u8, Box<[u8]>),
 }
 
 impl Permission {
-    fn id(self) -> u8 {
+    fn id(&self) -> u8 {
         match self {
             Permission::Dash7(_) => 0x42, // ALP_SPEC Undefined
+            Permission::Rfu(id, _) => *id,
+        }
+    }
+
+    /// Builds a Dash7 permission from its 8 byte token written in hexadecimal (an optional
+    /// `0x` prefix is accepted).
+    pub fn dash7_from_hex(token: &str) -> Result<Self, hex::FromHexError> {
+        let token = token
+            .strip_prefix("0x")
+            .or_else(|| token.strip_prefix("0X"))
+            .unwrap_or(token);
+        let mut data = [0; 8];
+        hex::decode_to_slice(token, &mut data)?;
+        Ok(Permission::Dash7(data))
+    }
+
+    /// Token of a Dash7 permission
+    pub fn token(&self) -> Option<&[u8; 8]> {
+        match self {
+            Permission::Dash7(token) => Some(token),
+            Permission::Rfu(_, _) => None,
         }
     }
 }
@@ -17,6 +48,7 @@ impl std::fmt::Display for Permission {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             Self::Dash7(data) => write!(f, "D7:0x{}", hex::encode_upper(data)),
+            Self::Rfu(id, data) => write!(f, "RFU[{}]:0x{}", id, hex::encode_upper(data)),
         }
     }
 }
@@ -24,7 +56,15 @@ impl std::fmt::Display for Permission {
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum PermissionDecodingError {
     MissingBytes(usize),
-    UnknownId(u8),
+}
+impl PermissionDecodingError {
+    /// Minimum number of bytes missing to decode the item, if the error is caused by a
+    /// truncated input.
+    pub fn missing_bytes(&self) -> Option<usize> {
+        match self {
+            Self::MissingBytes(n) => Some(*n),
+        }
+    }
 }
 
 impl Codec for Permission {
@@ -32,6 +72,9 @@ impl Codec for Permission {
     fn encoded_size(&self) -> usize {
         1 + match self {
             Permission::Dash7(_) => 8,
+            Permission::Rfu(_, data) => {
+                (unsafe { varint::size(data.len() as u32) }) as usize + data.len()
+            }
         }
     }
     unsafe fn encode_in(&self, out: &mut [u8]) -> usize {
@@ -41,6 +84,11 @@ impl Codec for Permission {
                 out[1..1 + token.len()].clone_from_slice(&token[..]);
                 8
             }
+            Permission::Rfu(_, data) => {
+                let size_size = varint::encode_in(data.len() as u32, &mut out[1..]) as usize;
+                out[1 + size_size..1 + size_size + data.len()].clone_from_slice(data);
+                size_size + data.len()
+            }
         }
     }
     fn decode(out: &[u8]) -> Result<WithSize<Self>, WithOffset<Self::Error>> {
@@ -50,6 +98,11 @@ impl Codec for Permission {
         let mut offset = 1;
         match out[0] {
             0x42 => {
+                if out.len() < offset + 8 {
+                    return Err(WithOffset::new_head(Self::Error::MissingBytes(
+                        offset + 8 - out.len(),
+                    )));
+                }
                 let mut token = [0; 8];
                 token.clone_from_slice(&out[offset..offset + 8]);
                 offset += 8;
@@ -58,11 +111,113 @@ impl Codec for Permission {
                     size: offset,
                 })
             }
-            x => Err(WithOffset::new_head(Self::Error::UnknownId(x))),
+            id => {
+                let WithSize {
+                    value: size,
+                    size: size_size,
+                } = varint::decode(&out[offset..]).map_err(|e| {
+                    let WithOffset {
+                        offset: off,
+                        value: StdError::MissingBytes(n),
+                    } = e;
+                    WithOffset::new(offset + off, Self::Error::MissingBytes(n))
+                })?;
+                offset += size_size;
+                let size = size as usize;
+                if out.len() < offset + size {
+                    return Err(WithOffset::new_head(Self::Error::MissingBytes(
+                        offset + size - out.len(),
+                    )));
+                }
+                Ok(WithSize {
+                    value: Permission::Rfu(id, out[offset..offset + size].into()),
+                    size: offset + size,
+                })
+            }
         }
     }
 }
 
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_tools::test_item;
+    use hex_literal::hex;
+
+    #[test]
+    fn test_dash7() {
+        test_item(
+            Permission::Dash7(hex!("0102030405060708")),
+            &hex!("42 0102030405060708"),
+        )
+    }
+
+    #[test]
+    fn test_rfu() {
+        test_item(
+            Permission::Rfu(0x12, Box::new(hex!("ABCD"))),
+            &hex!("12 02 ABCD"),
+        )
+    }
+
+    #[test]
+    fn test_rfu_bounded() {
+        let WithSize { value, size } = Permission::decode(&hex!("12 01 AB   CD")).unwrap();
+        assert_eq!(value, Permission::Rfu(0x12, Box::new([0xAB])));
+        assert_eq!(size, 3);
+        assert_eq!(
+            Permission::decode(&hex!("12 03 ABCD")),
+            Err(WithOffset::new_head(PermissionDecodingError::MissingBytes(
+                1
+            )))
+        );
+    }
+
+    #[test]
+    fn test_truncated_dash7() {
+        assert_eq!(
+            Permission::decode(&hex!("42 01020304")),
+            Err(WithOffset::new_head(PermissionDecodingError::MissingBytes(
+                4
+            )))
+        );
+        assert_eq!(
+            Permission::decode(&hex!("42")),
+            Err(WithOffset::new_head(PermissionDecodingError::MissingBytes(
+                8
+            )))
+        );
+    }
+
+    #[test]
+    fn test_dash7_from_hex() {
+        let permission = Permission::dash7_from_hex("0x0102030405060708").unwrap();
+        assert_eq!(permission.token(), Some(&hex!("0102030405060708")));
+        assert_eq!(
+            Permission::dash7_from_hex("0102030405060708"),
+            Ok(permission)
+        );
+        assert_eq!(
+            Permission::dash7_from_hex("01020304050607"),
+            Err(hex::FromHexError::InvalidStringLength)
+        );
+        assert!(Permission::dash7_from_hex("01020304050607ZZ").is_err());
+        assert_eq!(Permission::Rfu(0, Box::new([])).token(), None);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            Permission::Dash7(hex!("0102030405060708")).to_string(),
+            "D7:0x0102030405060708"
+        );
+        assert_eq!(
+            Permission::Rfu(0x12, Box::new(hex!("ABCD"))).to_string(),
+            "RFU[18]:0xABCD"
+        );
+    }
+}
+
 pub mod permission_level {
     pub const USER: u8 = 0;
     pub const ROOT: u8 = 1;
@@ -11,6 +11,7 @@ use crate::{
 use hex_literal::hex;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum QueryComparisonType {
     Inequal = 0,
     Equal = 1,
@@ -31,6 +32,26 @@ impl QueryComparisonType {
             x => return Err(x),
         })
     }
+
+    /// Whether the comparison holds, given the ordering of the actual value against the expected
+    /// one (`actual.cmp(&expected)`).
+    pub fn evaluate(&self, ordering: std::cmp::Ordering) -> bool {
+        use std::cmp::Ordering;
+        match self {
+            Self::Inequal => ordering != Ordering::Equal,
+            Self::Equal => ordering == Ordering::Equal,
+            Self::LessThan => ordering == Ordering::Less,
+            Self::LessThanOrEqual => ordering != Ordering::Greater,
+            Self::GreaterThan => ordering == Ordering::Greater,
+            Self::GreaterThanOrEqual => ordering != Ordering::Less,
+        }
+    }
+}
+impl std::convert::TryFrom<u8> for QueryComparisonType {
+    type Error = u8;
+    fn try_from(n: u8) -> Result<Self, Self::Error> {
+        Self::from(n)
+    }
 }
 impl std::fmt::Display for QueryComparisonType {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -50,6 +71,7 @@ impl std::fmt::Display for QueryComparisonType {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum QueryRangeComparisonType {
     NotInRange = 0,
     InRange = 1,
@@ -103,11 +125,26 @@ pub enum QueryOperandDecodingError {
     FileOffset1(FileOffsetDecodingError),
     FileOffset2(FileOffsetDecodingError),
     UnknownComparisonType(u8),
+    /// The start of a bitmap range is greater than its stop, making its bitmap size undefined
+    StartGreaterThanStop,
+}
+impl QueryOperandDecodingError {
+    /// Minimum number of bytes missing to decode the item, if the error is caused by a
+    /// truncated input.
+    pub fn missing_bytes(&self) -> Option<usize> {
+        match self {
+            Self::MissingBytes(n) => Some(*n),
+            Self::Size(e) => e.missing_bytes(),
+            Self::FileOffset1(e) | Self::FileOffset2(e) => e.missing_bytes(),
+            Self::UnknownComparisonType(_) | Self::StartGreaterThanStop => None,
+        }
+    }
 }
 
 // ALP_SPEC Does this fail if the content overflows the file?
 /// Checks if the file content exists.
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NonVoid {
     pub size: u32,
     pub file: FileOffset,
@@ -164,6 +201,55 @@ impl Codec for NonVoid {
         })
     }
 }
+#[test]
+fn test_query_comparison_type() {
+    use std::cmp::Ordering;
+    use std::convert::TryFrom;
+    let cases = [
+        (QueryComparisonType::Inequal, "NEQ", [true, false, true]),
+        (QueryComparisonType::Equal, "EQU", [false, true, false]),
+        (QueryComparisonType::LessThan, "LTH", [true, false, false]),
+        (
+            QueryComparisonType::LessThanOrEqual,
+            "LTE",
+            [true, true, false],
+        ),
+        (
+            QueryComparisonType::GreaterThan,
+            "GTH",
+            [false, false, true],
+        ),
+        (
+            QueryComparisonType::GreaterThanOrEqual,
+            "GTE",
+            [false, true, true],
+        ),
+    ];
+    for (comparison, name, results) in cases {
+        assert_eq!(
+            QueryComparisonType::try_from(comparison as u8),
+            Ok(comparison)
+        );
+        assert_eq!(comparison.to_string(), name);
+        for (ordering, result) in [Ordering::Less, Ordering::Equal, Ordering::Greater]
+            .iter()
+            .copied()
+            .zip(results)
+        {
+            assert_eq!(
+                comparison.evaluate(ordering),
+                result,
+                "{} {:?}",
+                name,
+                ordering
+            );
+        }
+    }
+    for n in 6..=7 {
+        assert_eq!(QueryComparisonType::try_from(n), Err(n));
+    }
+}
+
 #[test]
 fn test_non_void_query_operand() {
     test_item(
@@ -187,6 +273,7 @@ pub enum QueryValidationError {
 
 /// Compare file content, optionally masked, with 0.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ComparisonWithZero {
     pub signed_data: bool,
     pub comparison_type: QueryComparisonType,
@@ -273,6 +360,12 @@ impl Codec for ComparisonWithZero {
         })?;
         let mut offset = 1 + size_size;
         let mask = if mask_flag {
+            if out.len() < offset + size as usize {
+                return Err(WithOffset::new(
+                    0,
+                    Self::Error::MissingBytes(offset + size as usize - out.len()),
+                ));
+            }
             let mut data = vec![0u8; size as usize].into_boxed_slice();
             data.clone_from_slice(&out[offset..offset + size as usize]);
             offset += size as usize;
@@ -319,6 +412,7 @@ fn test_comparison_with_zero_operand() {
 
 /// Compare some file content optionally masked, with a value
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ComparisonWithValue {
     pub signed_data: bool,
     pub comparison_type: QueryComparisonType,
@@ -355,6 +449,48 @@ impl ComparisonWithValue {
         }
         Ok(())
     }
+
+    /// Raw big endian value, sign extended if `signed_data` is set.
+    ///
+    /// Returns `None` if the value is longer than 8 bytes or does not match `size`.
+    fn raw_value(&self) -> Option<u64> {
+        let size = self.value.len();
+        if size > 8 || size as u32 != self.size {
+            return None;
+        }
+        let mut value = self
+            .value
+            .iter()
+            .fold(0u64, |acc, byte| (acc << 8) | *byte as u64);
+        if self.signed_data && size > 0 && size < 8 && self.value[0] & 0x80 != 0 {
+            value |= u64::MAX << (size * 8);
+        }
+        Some(value)
+    }
+
+    /// Value as a signed integer.
+    ///
+    /// Returns `None` if the value is longer than 8 bytes, does not match `size` or is an
+    /// unsigned value too big to fit.
+    pub fn as_i64(&self) -> Option<i64> {
+        let value = self.raw_value()?;
+        if !self.signed_data && value > i64::MAX as u64 {
+            return None;
+        }
+        Some(value as i64)
+    }
+
+    /// Value as an unsigned integer.
+    ///
+    /// Returns `None` if the value is longer than 8 bytes, does not match `size` or is a
+    /// negative signed value.
+    pub fn as_u64(&self) -> Option<u64> {
+        let value = self.raw_value()?;
+        if self.signed_data && (value as i64) < 0 {
+            return None;
+        }
+        Some(value)
+    }
 }
 impl Codec for ComparisonWithValue {
     type Error = QueryOperandDecodingError;
@@ -412,6 +548,12 @@ impl Codec for ComparisonWithValue {
         })?;
         let mut offset = 1 + size_size;
         let mask = if mask_flag {
+            if out.len() < offset + size as usize {
+                return Err(WithOffset::new(
+                    0,
+                    Self::Error::MissingBytes(offset + size as usize - out.len()),
+                ));
+            }
             let mut data = vec![0u8; size as usize].into_boxed_slice();
             data.clone_from_slice(&out[offset..offset + size as usize]);
             offset += size as usize;
@@ -419,6 +561,12 @@ impl Codec for ComparisonWithValue {
         } else {
             None
         };
+        if out.len() < offset + size as usize {
+            return Err(WithOffset::new(
+                0,
+                Self::Error::MissingBytes(offset + size as usize - out.len()),
+            ));
+        }
         let mut value = vec![0u8; size as usize].into_boxed_slice();
         value.clone_from_slice(&out[offset..offset + size as usize]);
         offset += size as usize;
@@ -460,9 +608,54 @@ fn test_comparison_with_value_operand() {
         &hex!("41 03   090909  04 05"),
     )
 }
+#[test]
+fn test_comparison_with_value_integer() {
+    fn query(signed_data: bool, value: &[u8]) -> ComparisonWithValue {
+        ComparisonWithValue {
+            signed_data,
+            comparison_type: QueryComparisonType::Equal,
+            size: value.len() as u32,
+            mask: None,
+            value: value.into(),
+            file: FileOffset { id: 0, offset: 0 },
+        }
+    }
+    // Negative values
+    assert_eq!(query(true, &hex!("FE")).as_i64(), Some(-2));
+    assert_eq!(query(true, &hex!("FF38")).as_i64(), Some(-200));
+    assert_eq!(query(true, &hex!("FFFE7960")).as_i64(), Some(-100_000));
+    assert_eq!(
+        query(true, &hex!("8000000000000000")).as_i64(),
+        Some(i64::MIN)
+    );
+    assert_eq!(query(true, &hex!("FE")).as_u64(), None);
+    assert_eq!(query(true, &hex!("FFFFFFFFFFFFFFFF")).as_u64(), None);
+
+    // Positive signed values
+    assert_eq!(query(true, &hex!("7F")).as_i64(), Some(127));
+    assert_eq!(query(true, &hex!("7FFF")).as_u64(), Some(0x7FFF));
+
+    // Unsigned values
+    assert_eq!(query(false, &hex!("FE")).as_i64(), Some(0xFE));
+    assert_eq!(query(false, &hex!("FF38")).as_u64(), Some(0xFF38));
+    assert_eq!(query(false, &hex!("FFFE7960")).as_u64(), Some(0xFFFE7960));
+    assert_eq!(
+        query(false, &hex!("FFFFFFFFFFFFFFFF")).as_u64(),
+        Some(u64::MAX)
+    );
+    assert_eq!(query(false, &hex!("FFFFFFFFFFFFFFFF")).as_i64(), None);
+
+    // Unsupported sizes
+    assert_eq!(query(true, &hex!("000000000000000001")).as_i64(), None);
+    assert_eq!(query(false, &hex!("000000000000000001")).as_u64(), None);
+    let mut inconsistent = query(false, &hex!("01"));
+    inconsistent.size = 2;
+    assert_eq!(inconsistent.as_u64(), None);
+}
 
 /// Compare content of 2 files optionally masked
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ComparisonWithOtherFile {
     pub signed_data: bool,
     pub comparison_type: QueryComparisonType,
@@ -554,6 +747,12 @@ impl Codec for ComparisonWithOtherFile {
         })?;
         let mut offset = 1 + size_size;
         let mask = if mask_flag {
+            if out.len() < offset + size as usize {
+                return Err(WithOffset::new(
+                    0,
+                    Self::Error::MissingBytes(offset + size as usize - out.len()),
+                ));
+            }
             let mut data = vec![0u8; size as usize].into_boxed_slice();
             data.clone_from_slice(&out[offset..offset + size as usize]);
             offset += size as usize;
@@ -613,6 +812,7 @@ fn test_comparison_with_other_file_operand() {
 
 /// Check if the content of a file is (not) contained in the sent bitmap values
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BitmapRangeComparison {
     pub signed_data: bool,
     pub comparison_type: QueryRangeComparisonType,
@@ -642,19 +842,74 @@ impl std::fmt::Display for BitmapRangeComparison {
     }
 }
 impl BitmapRangeComparison {
+    /// Unsigned check that the file content at `file` is within `start..=stop`, and part of the
+    /// set described by `mask` if any (see [contains](#method.contains)).
+    ///
+    /// The bounds are encoded on as few bytes as `stop` needs. Fails if `start` is greater than
+    /// `stop` or if the mask size is not the [bitmap_size](#method.bitmap_size) of the range.
+    pub fn new(
+        start: u32,
+        stop: u32,
+        mask: Option<&[u8]>,
+        file: FileOffset,
+    ) -> Result<Self, QueryValidationError> {
+        let query = Self {
+            signed_data: false,
+            comparison_type: QueryRangeComparisonType::InRange,
+            size: (32 - stop.leading_zeros()).div_ceil(8).max(1),
+            start,
+            stop,
+            mask: mask.map(Into::into),
+            file,
+        };
+        query.validate()?;
+        Ok(query)
+    }
+
+    /// Size of the bitmap of a `start..=stop` range, as encoded on the wire.
+    ///
+    /// ALP SPEC: The specification gives a size of (stop - start + 6) / 8, which is one bit short
+    /// when (stop - start) % 8 is 0 or 1, leaving the last value of such ranges without a bit.
+    ///
+    /// `start` must not be greater than `stop`.
+    pub fn bitmap_size(start: u32, stop: u32) -> usize {
+        ((stop - start) as usize + 6) / 8
+    }
+
     pub fn validate(&self) -> Result<(), QueryValidationError> {
         if self.start > self.stop {
             return Err(QueryValidationError::StartGreaterThanStop);
         }
 
-        let bitmap_size = (self.stop - self.start + 6) / 8; // ALP SPEC: Thanks for the calculation
         if let Some(mask) = &self.mask {
-            if mask.len() != bitmap_size as usize {
+            if mask.len() != Self::bitmap_size(self.start, self.stop) {
                 return Err(QueryValidationError::BadMaskSize);
             }
         }
         Ok(())
     }
+
+    /// Tells whether `value` is part of the set described by the range and its bitmap.
+    ///
+    /// The range is inclusive. Without a bitmap, every value of the range is part of the
+    /// set. Otherwise, `value` is part of it if the bit `value - start` of the bitmap is set,
+    /// bit `n` being bit `n % 8` (least significant first) of byte `n / 8`.
+    ///
+    /// This does not take the `comparison_type` into account.
+    pub fn contains(&self, value: u32) -> bool {
+        if value < self.start || value > self.stop {
+            return false;
+        }
+        match &self.mask {
+            None => true,
+            Some(mask) => {
+                let n = (value - self.start) as usize;
+                mask.get(n / 8)
+                    .map(|byte| byte & (1 << (n % 8)) != 0)
+                    .unwrap_or(false)
+            }
+        }
+    }
 }
 impl Codec for BitmapRangeComparison {
     type Error = QueryOperandDecodingError;
@@ -707,6 +962,12 @@ impl Codec for BitmapRangeComparison {
         })?;
         let size = size32 as usize;
         let mut offset = 1 + size_size;
+        if out.len() < offset + 2 * size {
+            return Err(WithOffset::new(
+                0,
+                Self::Error::MissingBytes(offset + 2 * size - out.len()),
+            ));
+        }
         let mut raw_start = vec![0u8; size].into_boxed_slice();
         raw_start.clone_from_slice(&out[offset..offset + size]);
         offset += size;
@@ -720,10 +981,22 @@ impl Codec for BitmapRangeComparison {
             stop = (stop << 8) + raw_stop[i] as u32;
         }
         let mask = if mask_flag {
-            let bitmap_size = (stop - start + 6) / 8; // ALP SPEC: Thanks for the calculation
-            let mut bitmap = vec![0u8; bitmap_size as usize].into_boxed_slice();
-            bitmap.clone_from_slice(&out[offset..offset + bitmap_size as usize]);
-            offset += bitmap_size as usize;
+            if start > stop {
+                return Err(WithOffset::new(
+                    1 + size_size,
+                    Self::Error::StartGreaterThanStop,
+                ));
+            }
+            let bitmap_size = Self::bitmap_size(start, stop);
+            if out.len() < offset + bitmap_size {
+                return Err(WithOffset::new(
+                    0,
+                    Self::Error::MissingBytes(offset + bitmap_size - out.len()),
+                ));
+            }
+            let mut bitmap = vec![0u8; bitmap_size].into_boxed_slice();
+            bitmap.clone_from_slice(&out[offset..offset + bitmap_size]);
+            offset += bitmap_size;
             Some(bitmap)
         } else {
             None
@@ -770,10 +1043,97 @@ fn test_bitmap_range_comparison_operand() {
         &hex!("81 02 0003  0020  01020304  00 04"),
     )
 }
+#[test]
+fn test_bitmap_range_comparison_new() {
+    let file = FileOffset { id: 0, offset: 4 };
+    // 30 values need 30 bits
+    let query = BitmapRangeComparison::new(3, 32, Some(&hex!("01020304")), file).unwrap();
+    assert_eq!(query.size, 1);
+    assert_eq!(query.mask.as_deref(), Some(&hex!("01020304")[..]));
+    assert_eq!(
+        BitmapRangeComparison::new(3, 32, Some(&hex!("010203")), file),
+        Err(QueryValidationError::BadMaskSize)
+    );
+    assert_eq!(
+        BitmapRangeComparison::new(3, 32, Some(&hex!("0102030405")), file),
+        Err(QueryValidationError::BadMaskSize)
+    );
+    assert_eq!(
+        BitmapRangeComparison::new(33, 32, None, file),
+        Err(QueryValidationError::StartGreaterThanStop)
+    );
+    assert_eq!(BitmapRangeComparison::bitmap_size(5, 5), 0);
+    assert_eq!(BitmapRangeComparison::bitmap_size(0, 7), 1);
+    assert_eq!(BitmapRangeComparison::bitmap_size(0, 8), 1);
+    assert_eq!(BitmapRangeComparison::bitmap_size(0, 10), 2);
+    let query = BitmapRangeComparison::new(0x100, 0x108, Some(&hex!("01")), file).unwrap();
+    assert_eq!(query.size, 2);
+    test_item(query, &hex!("81 02 0100 0108  01  00 04"));
+}
+#[test]
+fn test_bitmap_range_comparison_spec_bitmap_size() {
+    // Frames of a conforming peer, with (stop - start) % 8 being 0 and 1
+    let file = FileOffset { id: 0, offset: 4 };
+    test_item(
+        BitmapRangeComparison::new(0, 8, Some(&hex!("FF")), file).unwrap(),
+        &hex!("81 01 00 08  FF  00 04"),
+    );
+    test_item(
+        BitmapRangeComparison::new(4, 5, Some(&[]), file).unwrap(),
+        &hex!("81 01 04 05  00 04"),
+    );
+}
+#[test]
+fn test_bitmap_range_comparison_contains() {
+    let mut query = BitmapRangeComparison {
+        signed_data: false,
+        comparison_type: QueryRangeComparisonType::InRange,
+        size: 1,
+        start: 10,
+        stop: 25,
+        mask: None,
+        file: FileOffset { id: 0, offset: 0 },
+    };
+    assert!(!query.contains(9));
+    assert!(query.contains(10));
+    assert!(query.contains(17));
+    assert!(query.contains(25));
+    assert!(!query.contains(26));
+
+    query.mask = Some(Box::new(hex!("FD 80")));
+    // Masked out
+    assert!(!query.contains(11));
+    assert!(query.contains(10));
+    assert!(query.contains(12));
+    assert!(!query.contains(18));
+    assert!(query.contains(25));
+    // Out of range
+    assert!(!query.contains(9));
+    assert!(!query.contains(26));
+}
+#[test]
+fn test_bitmap_range_comparison_decode_errors() {
+    assert_eq!(
+        BitmapRangeComparison::decode(&hex!("80 01 05 03  00 04")),
+        Err(WithOffset::new(
+            2,
+            QueryOperandDecodingError::StartGreaterThanStop
+        ))
+    );
+    // Truncated bitmap of 2 bytes
+    assert_eq!(
+        BitmapRangeComparison::decode(&hex!("80 01 00 10  FF")),
+        Err(WithOffset::new(
+            0,
+            QueryOperandDecodingError::MissingBytes(1)
+        ))
+    );
+}
 
 /// Compare some file content, optional masked, with an array of bytes and up to a certain number
 /// of errors.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StringTokenSearch {
     pub max_errors: u8,
     pub size: u32,
@@ -857,6 +1217,12 @@ impl Codec for StringTokenSearch {
         let size = size32 as usize;
         let mut offset = 1 + size_size;
         let mask = if mask_flag {
+            if out.len() < offset + size {
+                return Err(WithOffset::new(
+                    0,
+                    Self::Error::MissingBytes(offset + size - out.len()),
+                ));
+            }
             let mut data = vec![0u8; size].into_boxed_slice();
             data.clone_from_slice(&out[offset..offset + size]);
             offset += size;
@@ -864,6 +1230,12 @@ impl Codec for StringTokenSearch {
         } else {
             None
         };
+        if out.len() < offset + size {
+            return Err(WithOffset::new(
+                0,
+                Self::Error::MissingBytes(offset + size - out.len()),
+            ));
+        }
         let mut value = vec![0u8; size].into_boxed_slice();
         value.clone_from_slice(&out[offset..offset + size]);
         offset += size;
@@ -906,6 +1278,7 @@ fn test_string_token_search_operand() {
 
 /// The query operand provides a way to do optional actions. It represents a condition.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Query {
     NonVoid(NonVoid),
     ComparisonWithZero(ComparisonWithZero),
@@ -926,6 +1299,74 @@ impl std::fmt::Display for Query {
         }
     }
 }
+impl Query {
+    /// Unsigned, unmasked comparison of the file content at `offset` with `value`.
+    pub fn compare_value(
+        file_id: u8,
+        offset: u32,
+        comparison_type: QueryComparisonType,
+        value: &[u8],
+    ) -> Self {
+        Query::ComparisonWithValue(ComparisonWithValue {
+            signed_data: false,
+            comparison_type,
+            size: value.len() as u32,
+            mask: None,
+            value: value.into(),
+            file: FileOffset {
+                id: file_id,
+                offset,
+            },
+        })
+    }
+
+    /// Unsigned check that the file content at `offset` is within `start..=stop`.
+    ///
+    /// The bounds are encoded on as few bytes as `stop` needs.
+    pub fn in_range(file_id: u8, offset: u32, start: u32, stop: u32) -> Self {
+        let size = (32 - stop.leading_zeros()).div_ceil(8).max(1);
+        Query::BitmapRangeComparison(BitmapRangeComparison {
+            signed_data: false,
+            comparison_type: QueryRangeComparisonType::InRange,
+            size,
+            start,
+            stop,
+            mask: None,
+            file: FileOffset {
+                id: file_id,
+                offset,
+            },
+        })
+    }
+
+    pub fn validate(&self) -> Result<(), QueryValidationError> {
+        match self {
+            Query::NonVoid(v) => {
+                if v.size > varint::MAX {
+                    return Err(QueryValidationError::SizeTooBig);
+                }
+                Ok(())
+            }
+            Query::ComparisonWithZero(v) => v.validate(),
+            Query::ComparisonWithValue(v) => v.validate(),
+            Query::ComparisonWithOtherFile(v) => v.validate(),
+            Query::BitmapRangeComparison(v) => v.validate(),
+            Query::StringTokenSearch(v) => v.validate(),
+        }
+    }
+
+    /// Code identifying the kind of this query on the wire.
+    pub fn query_code(&self) -> QueryCode {
+        match self {
+            Query::NonVoid(_) => QueryCode::NonVoid,
+            Query::ComparisonWithZero(_) => QueryCode::ComparisonWithZero,
+            Query::ComparisonWithValue(_) => QueryCode::ComparisonWithValue,
+            Query::ComparisonWithOtherFile(_) => QueryCode::ComparisonWithOtherFile,
+            Query::BitmapRangeComparison(_) => QueryCode::BitmapRangeComparison,
+            Query::StringTokenSearch(_) => QueryCode::StringTokenSearch,
+        }
+    }
+}
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum QueryDecodingError {
     MissingBytes(usize),
@@ -937,6 +1378,22 @@ pub enum QueryDecodingError {
     BitmapRangeComparison(QueryOperandDecodingError),
     StringTokenSearch(QueryOperandDecodingError),
 }
+impl QueryDecodingError {
+    /// Minimum number of bytes missing to decode the item, if the error is caused by a
+    /// truncated input.
+    pub fn missing_bytes(&self) -> Option<usize> {
+        match self {
+            Self::MissingBytes(n) => Some(*n),
+            Self::UnknownQueryCode(_) => None,
+            Self::NonVoid(e)
+            | Self::ComparisonWithZero(e)
+            | Self::ComparisonWithValue(e)
+            | Self::ComparisonWithOtherFile(e)
+            | Self::BitmapRangeComparison(e)
+            | Self::StringTokenSearch(e) => e.missing_bytes(),
+        }
+    }
+}
 impl Codec for Query {
     type Error = QueryDecodingError;
     fn encoded_size(&self) -> usize {
@@ -960,9 +1417,12 @@ impl Codec for Query {
         }
     }
     fn decode(out: &[u8]) -> Result<WithSize<Self>, WithOffset<Self::Error>> {
-        match QueryCode::from(out[0] >> 5)
-            .map_err(|e| WithOffset::new_head(Self::Error::UnknownQueryCode(e)))?
-        {
+        if out.is_empty() {
+            return Err(WithOffset::new_head(Self::Error::MissingBytes(1)));
+        }
+        let code = QueryCode::from(out[0] >> 5)
+            .map_err(|e| WithOffset::new_head(Self::Error::UnknownQueryCode(e)))?;
+        match code {
             QueryCode::NonVoid => NonVoid::decode(out)
                 .map(|ok| ok.map_value(Query::NonVoid))
                 .map_err(|e| e.map_value(Self::Error::NonVoid)),
@@ -984,3 +1444,132 @@ impl Codec for Query {
         }
     }
 }
+
+#[test]
+fn test_query_code_consistency() {
+    // No wildcard arm: adding a QueryCode without its Query counterpart must not compile.
+    fn sample(code: QueryCode) -> Query {
+        let file = FileOffset { id: 1, offset: 2 };
+        match code {
+            QueryCode::NonVoid => Query::NonVoid(NonVoid { size: 1, file }),
+            QueryCode::ComparisonWithZero => Query::ComparisonWithZero(ComparisonWithZero {
+                signed_data: false,
+                comparison_type: QueryComparisonType::Equal,
+                size: 1,
+                mask: None,
+                file,
+            }),
+            QueryCode::ComparisonWithValue => Query::ComparisonWithValue(ComparisonWithValue {
+                signed_data: false,
+                comparison_type: QueryComparisonType::Equal,
+                size: 1,
+                mask: None,
+                value: Box::new([0x42]),
+                file,
+            }),
+            QueryCode::ComparisonWithOtherFile => {
+                Query::ComparisonWithOtherFile(ComparisonWithOtherFile {
+                    signed_data: false,
+                    comparison_type: QueryComparisonType::Equal,
+                    size: 1,
+                    mask: None,
+                    file1: file,
+                    file2: FileOffset { id: 3, offset: 4 },
+                })
+            }
+            QueryCode::BitmapRangeComparison => {
+                Query::BitmapRangeComparison(BitmapRangeComparison {
+                    signed_data: false,
+                    comparison_type: QueryRangeComparisonType::InRange,
+                    size: 1,
+                    start: 0,
+                    stop: 8,
+                    mask: None,
+                    file,
+                })
+            }
+            QueryCode::StringTokenSearch => Query::StringTokenSearch(StringTokenSearch {
+                max_errors: 0,
+                size: 1,
+                mask: None,
+                value: Box::new([0x42]),
+                file,
+            }),
+        }
+    }
+    for n in 0..8 {
+        let code = match QueryCode::from(n) {
+            Ok(code) => code,
+            Err(_) => continue,
+        };
+        let query = sample(code);
+        assert_eq!(query.query_code(), code);
+        let decoded = Query::decode(&query.encode()).unwrap().value;
+        assert_eq!(decoded.query_code(), code);
+        assert_eq!(decoded, query);
+    }
+}
+
+#[test]
+fn test_query_builders() {
+    test_item(
+        Query::compare_value(4, 5, QueryComparisonType::Equal, &[9, 9, 9]),
+        &hex!("41 03   090909  04 05"),
+    );
+    test_item(Query::in_range(0, 4, 3, 32), &hex!("91 01 03  20  00 04"));
+    assert_eq!(
+        Query::in_range(0, 4, 0, 0x100),
+        Query::BitmapRangeComparison(BitmapRangeComparison {
+            signed_data: false,
+            comparison_type: QueryRangeComparisonType::InRange,
+            size: 2,
+            start: 0,
+            stop: 0x100,
+            mask: None,
+            file: FileOffset { id: 0, offset: 4 },
+        })
+    );
+}
+#[test]
+fn test_query_decode_empty() {
+    assert_eq!(
+        Query::decode(&[]),
+        Err(WithOffset::new_head(QueryDecodingError::MissingBytes(1)))
+    );
+}
+
+#[test]
+fn test_query_file_offset_varint() {
+    // Every query reads its file offsets through the FileOffset codec: a multi-byte offset varint
+    // must be decoded the same way whatever the query.
+    let file = FileOffset {
+        id: 5,
+        offset: 0x3F_FF,
+    };
+    for data in [
+        &hex!("00 04   05 7FFF")[..],
+        &hex!("38 03   000102   05 7FFF"),
+        &hex!("41 03   090909   05 7FFF"),
+        &hex!("74 02   FFFF   05 7FFF   05 7FFF"),
+        &hex!("91 01   03 20   05 7FFF"),
+        &hex!("F2 04   FF00FF00 01020304   05 7FFF"),
+    ] {
+        let WithSize { value: query, size } = Query::decode(data).unwrap();
+        assert_eq!(size, data.len());
+        let files = match &query {
+            Query::NonVoid(q) => vec![q.file],
+            Query::ComparisonWithZero(q) => vec![q.file],
+            Query::ComparisonWithValue(q) => vec![q.file],
+            Query::ComparisonWithOtherFile(q) => vec![q.file1, q.file2],
+            Query::BitmapRangeComparison(q) => vec![q.file],
+            Query::StringTokenSearch(q) => vec![q.file],
+        };
+        assert!(files.iter().all(|f| *f == file), "{}", query);
+        assert_eq!(&query.encode()[..], data);
+        // Offset varint cut in the middle
+        assert_eq!(
+            Query::decode(&data[..data.len() - 1]).map_err(|e| e.value.missing_bytes()),
+            Err(Some(1))
+        );
+    }
+}
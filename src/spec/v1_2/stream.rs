@@ -0,0 +1,211 @@
+use super::{Action, Command, CommandParseFail};
+use crate::codec::{Codec, WithOffset, WithSize};
+#[cfg(all(test, feature = "actions"))]
+use hex_literal::hex;
+use std::num::NonZeroUsize;
+
+/// How a stream of back to back actions is split into commands.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StreamDelimiter {
+    /// Each `RequestTag` or `ResponseTag` action starts a new command.
+    Tag,
+    /// Each command is made of this number of actions (the last one may hold less).
+    ActionCount(NonZeroUsize),
+}
+
+/// Iterator over the commands of an unframed stream of actions.
+///
+/// See [decode_stream](fn.decode_stream.html).
+#[derive(Clone, Debug)]
+pub struct CommandStream<'a> {
+    data: &'a [u8],
+    offset: usize,
+    delimiter: StreamDelimiter,
+    next: Option<Action>,
+    done: bool,
+}
+
+impl<'a> CommandStream<'a> {
+    fn starts_command(&self, action: &Action) -> bool {
+        match self.delimiter {
+            StreamDelimiter::Tag => {
+                matches!(action, Action::RequestTag(_) | Action::ResponseTag(_))
+            }
+            StreamDelimiter::ActionCount(_) => false,
+        }
+    }
+
+    fn is_full(&self, actions: &[Action]) -> bool {
+        match self.delimiter {
+            StreamDelimiter::Tag => false,
+            StreamDelimiter::ActionCount(n) => actions.len() >= n.get(),
+        }
+    }
+}
+
+impl<'a> Iterator for CommandStream<'a> {
+    type Item = Result<Command, WithOffset<CommandParseFail>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let mut actions: Vec<Action> = self.next.take().into_iter().collect();
+        while !self.is_full(&actions) && self.offset < self.data.len() {
+            match Action::decode(&self.data[self.offset..]) {
+                Ok(WithSize { value, size }) => {
+                    self.offset += size;
+                    if !actions.is_empty() && self.starts_command(&value) {
+                        self.next = Some(value);
+                        break;
+                    }
+                    actions.push(value);
+                }
+                Err(WithOffset { offset, value }) => {
+                    self.done = true;
+                    return Some(Err(WithOffset {
+                        offset: self.offset + offset,
+                        value: CommandParseFail {
+                            actions,
+                            error: value,
+                        },
+                    }));
+                }
+            }
+        }
+        if actions.is_empty() {
+            self.done = true;
+            None
+        } else {
+            Some(Ok(Command { actions }))
+        }
+    }
+}
+
+/// Decodes a stream of commands written back to back without any framing.
+///
+/// The commands are delimited according to `delimiter`. The iteration stops after the first
+/// decoding error, whose offset is relative to the start of `data`.
+pub fn decode_stream(data: &[u8], delimiter: StreamDelimiter) -> CommandStream<'_> {
+    CommandStream {
+        data,
+        offset: 0,
+        delimiter,
+        next: None,
+        done: false,
+    }
+}
+
+/// Finds the first offset of `data` from which it decodes as a command of at least two actions
+/// consuming all of `data`, to resynchronize on a frame whose start was lost.
+///
+/// This is a heuristic: a wrong offset may happen to decode (in particular as one byte actions
+/// such as `Nop`), so the result is only a best effort guess.
+pub fn resync(data: &[u8]) -> Option<(usize, Command)> {
+    (0..data.len()).find_map(|offset| match Command::decode(&data[offset..]) {
+        Ok(command) if command.actions.len() >= 2 => Some((offset, command)),
+        _ => None,
+    })
+}
+
+#[cfg(feature = "actions")]
+#[test]
+fn test_decode_stream_by_tag() {
+    let data = hex!("B4 01  41 00 00 08  B4 02  C0 00  A3 03  01 00 00 04");
+    let commands: Vec<_> = decode_stream(&data, StreamDelimiter::Tag).collect();
+    assert_eq!(
+        commands,
+        vec![
+            Ok(Command {
+                actions: vec![
+                    Action::request_tag(true, 1),
+                    Action::read_file_data(false, true, 0, 0, 8),
+                ]
+            }),
+            Ok(Command {
+                actions: vec![
+                    Action::request_tag(true, 2),
+                    Action::nop(true, true),
+                    Action::nop(false, false),
+                ]
+            }),
+            Ok(Command {
+                actions: vec![
+                    Action::response_tag(true, false, 3),
+                    Action::read_file_data(false, false, 0, 0, 4),
+                ]
+            }),
+        ]
+    );
+}
+
+#[cfg(feature = "actions")]
+#[test]
+fn test_decode_stream_by_action_count() {
+    let data = hex!("00 40 80 C0 00");
+    let commands: Vec<_> = decode_stream(
+        &data,
+        StreamDelimiter::ActionCount(NonZeroUsize::new(2).unwrap()),
+    )
+    .collect();
+    assert_eq!(
+        commands,
+        vec![
+            Ok(Command {
+                actions: vec![Action::nop(false, false), Action::nop(false, true)]
+            }),
+            Ok(Command {
+                actions: vec![Action::nop(true, false), Action::nop(true, true)]
+            }),
+            Ok(Command {
+                actions: vec![Action::nop(false, false)]
+            }),
+        ]
+    );
+}
+
+#[cfg(feature = "actions")]
+#[test]
+fn test_decode_stream_error() {
+    let data = hex!("B4 01  C0  B4 02  3E");
+    let mut stream = decode_stream(&data, StreamDelimiter::Tag);
+    assert_eq!(
+        stream.next(),
+        Some(Ok(Command {
+            actions: vec![Action::request_tag(true, 1), Action::nop(true, true)]
+        }))
+    );
+    assert_eq!(
+        stream.next(),
+        Some(Err(WithOffset {
+            offset: 5,
+            value: CommandParseFail {
+                actions: vec![Action::request_tag(true, 2)],
+                error: super::action::ActionDecodingError::UnknownOpCode(0x3E),
+            },
+        }))
+    );
+    assert_eq!(stream.next(), None);
+    assert_eq!(decode_stream(&[], StreamDelimiter::Tag).next(), None);
+}
+
+#[cfg(feature = "actions")]
+#[test]
+fn test_resync() {
+    // Start of the frame lost, leaving an unknown opcode
+    let data = hex!("3E  41 00 00 08  B4 42");
+    assert_eq!(
+        resync(&data),
+        Some((
+            1,
+            Command {
+                actions: vec![
+                    Action::read_file_data(false, true, 0, 0, 8),
+                    Action::request_tag(true, 0x42),
+                ]
+            }
+        ))
+    );
+    // A single action is not trusted
+    assert_eq!(resync(&hex!("3E  41 00 00 08")), None);
+    assert_eq!(resync(&[]), None);
+}
@@ -0,0 +1,279 @@
+use crate::{
+    codec::{Codec, WithOffset, WithSize},
+    spec::v1_2::{
+        action::Status,
+        dash7::RetryMode,
+        operand::{self, Permission},
+        varint, Action, Command, CommandParseFail,
+    },
+};
+
+/// Non conformant content tolerated by [`Command::decode`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StrictViolation {
+    /// `field` holds a value reserved for future use by the specification
+    Reserved { field: &'static str, value: u8 },
+    /// The length announced in `field` differs from the actual length of the item
+    LengthMismatch {
+        field: &'static str,
+        declared: u32,
+        actual: u32,
+    },
+    /// Bits ignored by the decoder are set: the byte is `found` instead of `expected`
+    IgnoredBits { found: u8, expected: u8 },
+    /// The action spans `size` bytes while its minimal encoding (shortest varints) only takes
+    /// `minimal` bytes
+    NonMinimalEncoding { size: usize, minimal: usize },
+}
+impl std::fmt::Display for StrictViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Reserved { field, value } => write!(f, "reserved {} {}", field, value),
+            Self::LengthMismatch {
+                field,
+                declared,
+                actual,
+            } => write!(f, "{} announces {} bytes, got {}", field, declared, actual),
+            Self::IgnoredBits { found, expected } => write!(
+                f,
+                "ignored bits set: 0x{:02X} instead of 0x{:02X}",
+                found, expected
+            ),
+            Self::NonMinimalEncoding { size, minimal } => write!(
+                f,
+                "non minimal encoding: {} bytes instead of {}",
+                size, minimal
+            ),
+        }
+    }
+}
+
+/// Error of [`Command::decode_strict`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum StrictDecodeError {
+    /// The command cannot be decoded at all
+    Parse(WithOffset<CommandParseFail>),
+    /// The command decodes but is not spec conformant
+    Violation(WithOffset<StrictViolation>),
+}
+impl From<WithOffset<CommandParseFail>> for StrictDecodeError {
+    fn from(e: WithOffset<CommandParseFail>) -> Self {
+        Self::Parse(e)
+    }
+}
+
+/// Reserved value held by the action, with its offset in the encoded action.
+fn reserved_value(action: &Action) -> Option<WithOffset<StrictViolation>> {
+    match action {
+        // opcode, interface id, qos
+        Action::Forward(forward) => match &forward.conf {
+            operand::InterfaceConfiguration::D7asp(conf) if conf.qos.retry != RetryMode::No => {
+                Some(WithOffset::new(
+                    2,
+                    StrictViolation::Reserved {
+                        field: "qos.retry",
+                        value: conf.qos.retry as u8,
+                    },
+                ))
+            }
+            _ => None,
+        },
+        // opcode, level, permission id
+        Action::PermissionRequest(request) => match &request.permission {
+            Permission::Rfu(id, _) => Some(WithOffset::new(
+                2,
+                StrictViolation::Reserved {
+                    field: "permission.id",
+                    value: *id,
+                },
+            )),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Length field of the encoded action (`data`) disagreeing with the decoded action, with its
+/// offset in the encoded action.
+fn length_mismatch(action: &Action, data: &[u8]) -> Option<WithOffset<StrictViolation>> {
+    match action {
+        // opcode, interface id, length
+        Action::Status(Status::Interface(operand::InterfaceStatus::D7asp(status))) => {
+            let declared = varint::decode(&data[2..]).ok()?.value;
+            let actual = status.encoded_size() as u32;
+            if declared != actual {
+                Some(WithOffset::new(
+                    2,
+                    StrictViolation::LengthMismatch {
+                        field: "interface_status.len",
+                        declared,
+                        actual,
+                    },
+                ))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+pub(crate) fn decode_strict(out: &[u8]) -> Result<Command, StrictDecodeError> {
+    let mut actions = vec![];
+    let mut offset = 0;
+    while offset < out.len() {
+        let WithSize {
+            value: action,
+            size,
+        } = match Action::decode(&out[offset..]) {
+            Ok(decoded) => decoded,
+            Err(WithOffset { offset: off, value }) => {
+                return Err(StrictDecodeError::Parse(WithOffset::new(
+                    offset + off,
+                    CommandParseFail {
+                        actions,
+                        error: value,
+                    },
+                )))
+            }
+        };
+        let original = &out[offset..offset + size];
+        if let Some(violation) =
+            reserved_value(&action).or_else(|| length_mismatch(&action, original))
+        {
+            return Err(StrictDecodeError::Violation(violation.shift(offset)));
+        }
+        let encoded = action.encode();
+        if let Some(i) = (0..size).find(|i| encoded.get(*i) != Some(&original[*i])) {
+            let violation = if encoded.len() != size {
+                StrictViolation::NonMinimalEncoding {
+                    size,
+                    minimal: encoded.len(),
+                }
+            } else {
+                StrictViolation::IgnoredBits {
+                    found: original[i],
+                    expected: encoded[i],
+                }
+            };
+            return Err(StrictDecodeError::Violation(WithOffset::new(
+                offset + i,
+                violation,
+            )));
+        }
+        actions.push(action);
+        offset += size;
+    }
+    Ok(Command { actions })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use hex_literal::hex;
+
+    #[cfg(feature = "actions")]
+    #[test]
+    fn test_conformant() {
+        let data = hex!("B4 42   41 00 00 08");
+        assert_eq!(
+            Command::decode_strict(&data),
+            Ok(Command::decode(&data).unwrap())
+        );
+    }
+
+    #[cfg(feature = "actions")]
+    #[test]
+    fn test_ignored_bits() {
+        // Bit 6 of a request tag is not used
+        let data = hex!("00   F4 42");
+        assert_eq!(Command::decode(&data).unwrap().encode()[1], 0xB4);
+        let err = Command::decode_strict(&data).unwrap_err();
+        assert_eq!(
+            err,
+            StrictDecodeError::Violation(WithOffset::new(
+                1,
+                StrictViolation::IgnoredBits {
+                    found: 0xF4,
+                    expected: 0xB4
+                }
+            ))
+        );
+    }
+
+    #[cfg(feature = "actions")]
+    #[test]
+    fn test_reserved_retry_mode() {
+        let data = hex!("00   32 D7 1A 23 34  37 FF ABCD");
+        assert!(Command::decode(&data).is_ok());
+        let err = Command::decode_strict(&data).unwrap_err();
+        assert_eq!(
+            err,
+            StrictDecodeError::Violation(WithOffset::new(
+                3,
+                StrictViolation::Reserved {
+                    field: "qos.retry",
+                    value: 3
+                }
+            ))
+        );
+        if let StrictDecodeError::Violation(v) = err {
+            assert_eq!(v.value.to_string(), "reserved qos.retry 3");
+        }
+    }
+
+    #[cfg(feature = "actions")]
+    #[test]
+    fn test_interface_status_length_mismatch() {
+        let status = hex!("01 0123 02 03 04 B0 06 07 0800 0900   37 FF ABCD  0011223344");
+        let data = [&hex!("62 D7 20")[..], &status[..]].concat();
+        let cmd = Command::decode(&data).unwrap();
+        assert_eq!(cmd.encoded_size(), data.len());
+        assert_eq!(
+            Command::decode_strict(&data),
+            Err(StrictDecodeError::Violation(WithOffset::new(
+                2,
+                StrictViolation::LengthMismatch {
+                    field: "interface_status.len",
+                    declared: 0x20,
+                    actual: 0x16,
+                }
+            )))
+        );
+        let data = [&hex!("62 D7 16")[..], &status[..]].concat();
+        assert_eq!(Command::decode_strict(&data), Ok(cmd));
+    }
+
+    #[cfg(feature = "actions")]
+    #[test]
+    fn test_non_minimal_varint() {
+        // Read file data with a 2 bytes offset varint holding 0, followed by a request tag
+        let data = hex!("01 09 40 00 04   B4 42");
+        assert!(Command::decode(&data).is_ok());
+        let err = Command::decode_strict(&data).unwrap_err();
+        assert_eq!(
+            err,
+            StrictDecodeError::Violation(WithOffset::new(
+                2,
+                StrictViolation::NonMinimalEncoding {
+                    size: 5,
+                    minimal: 4
+                }
+            ))
+        );
+        if let StrictDecodeError::Violation(v) = err {
+            assert_eq!(
+                v.value.to_string(),
+                "non minimal encoding: 5 bytes instead of 4"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert!(matches!(
+            Command::decode_strict(&hex!("01 00")),
+            Err(StrictDecodeError::Parse(_))
+        ));
+    }
+}
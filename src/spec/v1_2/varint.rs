@@ -24,7 +24,7 @@ pub unsafe fn size(n: u32) -> u8 {
     }
 }
 
-/// Encode the value into a varint.
+/// Encode the value into a varint, on the minimal number of bytes (see [size]).
 ///
 /// # Safety
 /// Only call this on u32 that are less than 0x3F_FF_FF_FF.
@@ -57,6 +57,47 @@ pub fn decode(out: &[u8]) -> Result<WithSize<u32>, WithOffset<StdError>> {
     Ok(WithSize { value: ret, size })
 }
 
+/// Error of [decode_minimal]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MinimalDecodingError {
+    MissingBytes(usize),
+    /// The value is encoded on `size` bytes while it fits in less
+    NonMinimal {
+        value: u32,
+        size: usize,
+    },
+}
+impl MinimalDecodingError {
+    /// Minimum number of bytes missing to decode the item, if the error is caused by a
+    /// truncated input.
+    pub fn missing_bytes(&self) -> Option<usize> {
+        match self {
+            Self::MissingBytes(n) => Some(*n),
+            Self::NonMinimal { .. } => None,
+        }
+    }
+}
+impl From<StdError> for MinimalDecodingError {
+    fn from(e: StdError) -> Self {
+        match e {
+            StdError::MissingBytes(n) => Self::MissingBytes(n),
+        }
+    }
+}
+
+/// Decode a byte array as a varint, rejecting the values not encoded on the minimal number of
+/// bytes, as the specification requires (e.g. `40 05` for 5).
+pub fn decode_minimal(out: &[u8]) -> Result<WithSize<u32>, WithOffset<MinimalDecodingError>> {
+    let WithSize { value, size } = decode(out).map_err(|e| e.map_value(Into::into))?;
+    if unsafe { self::size(value) } as usize != size {
+        return Err(WithOffset::new_head(MinimalDecodingError::NonMinimal {
+            value,
+            size,
+        }));
+    }
+    Ok(WithSize { value, size })
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -104,4 +145,35 @@ mod test {
         test_ok(&hex!("BF FF FF"), 0x3F_FF_FF, 3);
         test_ok(&hex!("FF FF FF FF"), 0x3F_FF_FF_FF, 4);
     }
+
+    #[test]
+    fn test_decode_minimal() {
+        for data in [
+            &[0][..],
+            &hex!("3F"),
+            &hex!("7F FF"),
+            &hex!("40 40"),
+            &hex!("FF FF FF FF"),
+        ] {
+            assert_eq!(decode_minimal(data), Ok(decode(data).unwrap()));
+        }
+        assert_eq!(
+            decode_minimal(&hex!("40 05")),
+            Err(WithOffset::new_head(MinimalDecodingError::NonMinimal {
+                value: 5,
+                size: 2,
+            }))
+        );
+        assert_eq!(
+            decode_minimal(&hex!("C0 00 3F FF")),
+            Err(WithOffset::new_head(MinimalDecodingError::NonMinimal {
+                value: 0x3F_FF,
+                size: 4,
+            }))
+        );
+        assert_eq!(
+            decode_minimal(&hex!("80 00")),
+            Err(WithOffset::new_head(MinimalDecodingError::MissingBytes(1)))
+        );
+    }
 }
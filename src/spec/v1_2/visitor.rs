@@ -0,0 +1,108 @@
+use crate::spec::v1_2::action::{
+    Action, Chunk, CopyFile, FileDataAction, FileIdAction, FilePropertiesAction, Forward,
+    IndirectForward, Logic, Nop, PermissionRequest, QueryAction, ReadFileData, RequestTag,
+    ResponseTag, Status,
+};
+
+/// Handler of the actions of a command, one method per action kind.
+///
+/// Every method does nothing by default, so that implementers only override the actions they
+/// care about. See [Command::accept](super::Command::accept).
+#[allow(unused_variables)]
+pub trait ActionVisitor {
+    fn visit_nop(&mut self, action: &Nop) {}
+    fn visit_read_file_data(&mut self, action: &ReadFileData) {}
+    fn visit_read_file_properties(&mut self, action: &FileIdAction) {}
+    fn visit_write_file_data(&mut self, action: &FileDataAction) {}
+    fn visit_write_file_properties(&mut self, action: &FilePropertiesAction) {}
+    fn visit_action_query(&mut self, action: &QueryAction) {}
+    fn visit_break_query(&mut self, action: &QueryAction) {}
+    fn visit_permission_request(&mut self, action: &PermissionRequest) {}
+    fn visit_verify_checksum(&mut self, action: &QueryAction) {}
+    fn visit_exist_file(&mut self, action: &FileIdAction) {}
+    fn visit_create_new_file(&mut self, action: &FilePropertiesAction) {}
+    fn visit_delete_file(&mut self, action: &FileIdAction) {}
+    fn visit_restore_file(&mut self, action: &FileIdAction) {}
+    fn visit_flush_file(&mut self, action: &FileIdAction) {}
+    fn visit_copy_file(&mut self, action: &CopyFile) {}
+    fn visit_execute_file(&mut self, action: &FileIdAction) {}
+    fn visit_return_file_data(&mut self, action: &FileDataAction) {}
+    fn visit_return_file_properties(&mut self, action: &FilePropertiesAction) {}
+    fn visit_status(&mut self, action: &Status) {}
+    fn visit_response_tag(&mut self, action: &ResponseTag) {}
+    fn visit_chunk(&mut self, action: &Chunk) {}
+    fn visit_logic(&mut self, action: &Logic) {}
+    fn visit_forward(&mut self, action: &Forward) {}
+    fn visit_indirect_forward(&mut self, action: &IndirectForward) {}
+    fn visit_request_tag(&mut self, action: &RequestTag) {}
+}
+
+impl Action {
+    /// Calls the method of `visitor` handling this kind of action
+    pub fn accept<V: ActionVisitor + ?Sized>(&self, visitor: &mut V) {
+        match self {
+            Self::Nop(a) => visitor.visit_nop(a),
+            Self::ReadFileData(a) => visitor.visit_read_file_data(a),
+            Self::ReadFileProperties(a) => visitor.visit_read_file_properties(a),
+            Self::WriteFileData(a) => visitor.visit_write_file_data(a),
+            Self::WriteFileProperties(a) => visitor.visit_write_file_properties(a),
+            Self::ActionQuery(a) => visitor.visit_action_query(a),
+            Self::BreakQuery(a) => visitor.visit_break_query(a),
+            Self::PermissionRequest(a) => visitor.visit_permission_request(a),
+            Self::VerifyChecksum(a) => visitor.visit_verify_checksum(a),
+            Self::ExistFile(a) => visitor.visit_exist_file(a),
+            Self::CreateNewFile(a) => visitor.visit_create_new_file(a),
+            Self::DeleteFile(a) => visitor.visit_delete_file(a),
+            Self::RestoreFile(a) => visitor.visit_restore_file(a),
+            Self::FlushFile(a) => visitor.visit_flush_file(a),
+            Self::CopyFile(a) => visitor.visit_copy_file(a),
+            Self::ExecuteFile(a) => visitor.visit_execute_file(a),
+            Self::ReturnFileData(a) => visitor.visit_return_file_data(a),
+            Self::ReturnFileProperties(a) => visitor.visit_return_file_properties(a),
+            Self::Status(a) => visitor.visit_status(a),
+            Self::ResponseTag(a) => visitor.visit_response_tag(a),
+            Self::Chunk(a) => visitor.visit_chunk(a),
+            Self::Logic(a) => visitor.visit_logic(a),
+            Self::Forward(a) => visitor.visit_forward(a),
+            Self::IndirectForward(a) => visitor.visit_indirect_forward(a),
+            Self::RequestTag(a) => visitor.visit_request_tag(a),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::spec::v1_2::Command;
+
+    #[derive(Default)]
+    struct ReadCollector {
+        request_id: Option<u8>,
+        reads: Vec<(u8, u32, u32)>,
+    }
+    impl ActionVisitor for ReadCollector {
+        fn visit_read_file_data(&mut self, action: &ReadFileData) {
+            self.reads
+                .push((action.file_id, action.offset, action.size));
+        }
+        fn visit_request_tag(&mut self, action: &RequestTag) {
+            self.request_id = Some(action.id);
+        }
+    }
+
+    #[test]
+    fn test_accept() {
+        let cmd = Command {
+            actions: vec![
+                Action::request_tag(true, 0x42),
+                Action::read_file_data(false, true, 1, 0, 8),
+                Action::nop(false, false),
+                Action::read_file_data(false, true, 2, 4, 2),
+            ],
+        };
+        let mut collector = ReadCollector::default();
+        cmd.accept(&mut collector);
+        assert_eq!(collector.request_id, Some(0x42));
+        assert_eq!(collector.reads, vec![(1, 0, 8), (2, 4, 2)]);
+    }
+}
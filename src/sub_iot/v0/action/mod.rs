@@ -409,6 +409,50 @@ mod test_codec {
     use super::*;
     use crate::spec::v1_2::data;
 
+    crate::test_tools::opcode_coverage!();
+
+    /// Sub-IoT uses the opcode values of the specification.
+    const OPCODE_TABLE: &[(OpCode, u8)] = &[
+        (OpCode::Nop, 0),
+        (OpCode::ReadFileData, 1),
+        (OpCode::ReadFileProperties, 2),
+        (OpCode::WriteFileData, 4),
+        (OpCode::WriteFileProperties, 6),
+        (OpCode::ActionQuery, 8),
+        (OpCode::BreakQuery, 9),
+        (OpCode::PermissionRequest, 10),
+        (OpCode::VerifyChecksum, 11),
+        (OpCode::ExistFile, 16),
+        (OpCode::CreateNewFile, 17),
+        (OpCode::DeleteFile, 18),
+        (OpCode::RestoreFile, 19),
+        (OpCode::FlushFile, 20),
+        (OpCode::CopyFile, 23),
+        (OpCode::ExecuteFile, 31),
+        (OpCode::ReturnFileData, 32),
+        (OpCode::ReturnFileProperties, 33),
+        (OpCode::Status, 34),
+        (OpCode::ResponseTag, 35),
+        (OpCode::Chunk, 48),
+        (OpCode::Logic, 49),
+        (OpCode::Forward, 50),
+        (OpCode::IndirectForward, 51),
+        (OpCode::RequestTag, 52),
+        (OpCode::Extension, 63),
+    ];
+
+    #[test]
+    fn opcode_discriminants() {
+        for (op_code, value) in OPCODE_TABLE.iter().copied() {
+            assert_eq!(op_code as u8, value, "{}", op_code);
+            assert_eq!(OpCode::from(value), Ok(op_code));
+        }
+        assert_eq!(
+            (0..=0xFF).filter(|n| OpCode::from(*n).is_ok()).count(),
+            OPCODE_TABLE.len()
+        );
+    }
+
     #[test]
     fn nop() {
         test_item(
@@ -1066,7 +1110,7 @@ mod test_display {
                 seq: 7,
                 resp_to: 8,
                 fof: 9,
-                access_class: 0xFF,
+                access_class: dash7::AccessClass(0xFF),
                 address: dash7::Address::Vid([0xAB, 0xCD]),
                 nls_state: dash7::NlsState::AesCcm32(hex!("00 11 22 33 44")),
             }
@@ -1117,14 +1161,14 @@ mod test_display {
                         retry: dash7::RetryMode::No,
                         resp: dash7::RespMode::Any,
                     },
-                    to: 0x23,
+                    to: dash7::CompressedTime(0x23),
                     nls_method: dash7::NlsMethod::AesCcm32,
                     access_class: 0xFF,
                     address: dash7::Address::Vid([0xAB, 0xCD]),
                 }),
             })
             .to_string(),
-            "FWD[R]D7:0X,35|0xFF,NLS[7],VID[ABCD]"
+            "FWD[R]D7:0X,to=12s|0xFF,NLS[7],VID[ABCD]"
         );
     }
 
@@ -1,7 +1,8 @@
 use crate::codec::{Codec, WithOffset, WithSize};
 pub use crate::spec::v1_2::dash7::{
-    Address, AddressType, InterfaceConfigurationDecodingError, InterfaceStatus, NlsMethod,
-    NlsState, Qos, QosDecodingError, RespMode, RetryMode,
+    AccessClass, Address, AddressType, CompressedSeconds, CompressedTime,
+    InterfaceConfigurationDecodingError, InterfaceStatus, NlsMethod, NlsState, Qos,
+    QosDecodingError, RespMode, RetryMode,
 };
 #[cfg(test)]
 use crate::test_tools::test_item;
@@ -22,7 +23,7 @@ pub struct InterfaceConfiguration {
     /// then we can aggregate the requests, to avoid advertising twice. Another example would be if
     /// the target sends us a packet, the modem can aggregate our request to the response of the
     /// request of the target.
-    pub to: u8,
+    pub to: CompressedSeconds,
     /// Access class of the targeted listening device
     pub access_class: u8,
     /// Security method
@@ -33,14 +34,13 @@ pub struct InterfaceConfiguration {
 
 impl std::fmt::Display for InterfaceConfiguration {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(
+        crate::spec::v1_2::dash7::fmt_interface_configuration_common(
             f,
-            "{},{}|0x{},{},{}",
-            self.qos,
+            &self.qos,
             self.to,
-            hex::encode_upper([self.access_class]),
+            self.access_class.into(),
             self.nls_method,
-            self.address
+            &self.address,
         )
     }
 }
@@ -52,7 +52,7 @@ impl Codec for InterfaceConfiguration {
     }
     unsafe fn encode_in(&self, out: &mut [u8]) -> usize {
         self.qos.encode_in(out);
-        out[1] = self.to;
+        out[1] = self.to.0;
         out[2] = ((self.address.id_type() as u8) << 4) | (self.nls_method as u8);
         out[3] = self.access_class;
         4 + self.address.encode_in(&mut out[4..])
@@ -67,9 +67,9 @@ impl Codec for InterfaceConfiguration {
             value: qos,
             size: qos_size,
         } = Qos::decode(out).map_err(|e| e.map_value(Self::Error::Qos))?;
-        let to = out[1];
+        let to = CompressedTime(out[1]);
         let address_type = AddressType::from((out[2] & 0x30) >> 4);
-        let nls_method = unsafe { NlsMethod::from(out[2] & 0x0F) };
+        let nls_method = unsafe { NlsMethod::from(out[2] & 0x07) };
         let access_class = out[3];
         let WithSize {
             value: address,
@@ -101,7 +101,7 @@ fn test_interface_configuration() {
                 retry: RetryMode::No,
                 resp: RespMode::Any,
             },
-            to: 0x23,
+            to: CompressedTime(0x23),
             nls_method: NlsMethod::AesCcm32,
             access_class: 0xFF,
             address: Address::Vid([0xAB, 0xCD]),
@@ -118,7 +118,7 @@ fn test_interface_configuration_with_address_nbid() {
                 retry: RetryMode::No,
                 resp: RespMode::Any,
             },
-            to: 0x23,
+            to: CompressedTime(0x23),
             nls_method: NlsMethod::None,
             access_class: 0x00,
             address: Address::NbId(0x15),
@@ -134,7 +134,7 @@ fn test_interface_configuration_with_address_noid() {
                 retry: RetryMode::No,
                 resp: RespMode::Any,
             },
-            to: 0x23,
+            to: CompressedTime(0x23),
             nls_method: NlsMethod::AesCbcMac128,
             access_class: 0x24,
             address: Address::NoId,
@@ -150,7 +150,7 @@ fn test_interface_configuration_with_address_uid() {
                 retry: RetryMode::No,
                 resp: RespMode::Any,
             },
-            to: 0x23,
+            to: CompressedTime(0x23),
             nls_method: NlsMethod::AesCcm64,
             access_class: 0x48,
             address: Address::Uid([0, 1, 2, 3, 4, 5, 6, 7]),
@@ -166,7 +166,7 @@ fn test_interface_configuration_with_address_vid() {
                 retry: RetryMode::No,
                 resp: RespMode::Any,
             },
-            to: 0x23,
+            to: CompressedTime(0x23),
             nls_method: NlsMethod::AesCcm32,
             access_class: 0xFF,
             address: Address::Vid([0xAB, 0xCD]),
@@ -24,16 +24,26 @@ pub struct Command {
 impl std::fmt::Display for Command {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "[")?;
-        let end = self.actions.len() - 1;
         for (i, action) in self.actions.iter().enumerate() {
-            write!(f, "{}", action)?;
-            if i != end {
+            if i != 0 {
                 write!(f, "; ")?;
             }
+            write!(f, "{}", action)?;
         }
         write!(f, "]")
     }
 }
+impl From<Command> for Vec<u8> {
+    fn from(command: Command) -> Self {
+        command.encode().into_vec()
+    }
+}
+impl std::convert::TryFrom<&[u8]> for Command {
+    type Error = WithOffset<CommandParseFail>;
+    fn try_from(out: &[u8]) -> Result<Self, Self::Error> {
+        Self::decode(out)
+    }
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct CommandParseFail {
@@ -90,31 +100,65 @@ impl Command {
         Ok(Self { actions })
     }
 
+    /// First request tag of the command, if any
+    pub fn request_tag(&self) -> Option<&action::RequestTag> {
+        self.actions.iter().find_map(|action| match action {
+            Action::RequestTag(tag) => Some(tag),
+            _ => None,
+        })
+    }
+
+    /// First response tag of the command, if any
+    pub fn response_tag(&self) -> Option<&action::ResponseTag> {
+        self.actions.iter().find_map(|action| match action {
+            Action::ResponseTag(tag) => Some(tag),
+            _ => None,
+        })
+    }
+
+    /// Payload of the first interface [Status](action::Status) of the command, if any: the
+    /// reception conditions of a response.
+    pub fn interface_status(&self) -> Option<&operand::InterfaceStatus> {
+        self.actions.iter().find_map(|action| match action {
+            Action::Status(action::Status::Interface(status)) => Some(status),
+            _ => None,
+        })
+    }
+
     pub fn request_id(&self) -> Option<u8> {
-        for action in self.actions.iter() {
-            if let Action::RequestTag(action::RequestTag { id, .. }) = action {
-                return Some(*id);
-            }
-        }
-        None
+        self.request_tag().map(|tag| tag.id)
     }
 
     pub fn response_id(&self) -> Option<u8> {
-        for action in self.actions.iter() {
-            if let Action::ResponseTag(action::ResponseTag { id, .. }) = action {
-                return Some(*id);
-            }
-        }
-        None
+        self.response_tag().map(|tag| tag.id)
+    }
+
+    /// Identifiers of every request tag of the command, in order.
+    ///
+    /// A well formed command has at most one.
+    pub fn all_request_ids(&self) -> Vec<u8> {
+        self.actions
+            .iter()
+            .filter_map(|action| match action {
+                Action::RequestTag(tag) => Some(tag.id),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Identifiers of every response tag of the command, in order.
+    pub fn all_response_ids(&self) -> Vec<u8> {
+        self.actions
+            .iter()
+            .filter_map(|action| match action {
+                Action::ResponseTag(tag) => Some(tag.id),
+                _ => None,
+            })
+            .collect()
     }
 
     pub fn is_last_response(&self) -> bool {
-        for action in self.actions.iter() {
-            if let Action::ResponseTag(action::ResponseTag { eop, .. }) = action {
-                return *eop;
-            }
-        }
-        false
+        self.response_tag().map(|tag| tag.eop).unwrap_or(false)
     }
 }
 #[test]
@@ -167,6 +211,15 @@ fn test_command_display() {
     );
 }
 
+#[test]
+fn test_empty_command() {
+    let cmd = Command::default();
+    assert_eq!(cmd.to_string(), "[]");
+    assert_eq!(cmd.encoded_size(), 0);
+    assert!(cmd.encode().is_empty());
+    assert_eq!(Command::decode(&[]), Ok(cmd));
+}
+
 #[test]
 fn test_command_request_id() {
     assert_eq!(
@@ -192,6 +245,38 @@ fn test_command_request_id() {
     );
 }
 
+#[test]
+fn test_interface_status() {
+    let response = Command::decode(&hex!("A3 08   62 00 00")).unwrap();
+    assert_eq!(
+        response.interface_status(),
+        Some(&operand::InterfaceStatus::Host)
+    );
+    assert_eq!(
+        Command {
+            actions: vec![Action::response_tag(true, false, 8)],
+        }
+        .interface_status(),
+        None
+    );
+}
+
+#[test]
+fn test_command_all_ids() {
+    let cmd = Command {
+        actions: vec![
+            Action::request_tag(false, 44),
+            Action::nop(true, false),
+            Action::response_tag(true, false, 66),
+            Action::request_tag(true, 45),
+        ],
+    };
+    assert_eq!(cmd.request_id(), Some(44));
+    assert_eq!(cmd.all_request_ids(), vec![44, 45]);
+    assert_eq!(cmd.all_response_ids(), vec![66]);
+    assert!(Command::default().all_request_ids().is_empty());
+}
+
 #[test]
 fn test_comman_response_id() {
     assert_eq!(
@@ -251,3 +336,14 @@ fn test_command_is_last_response() {
     }
     .is_last_response());
 }
+
+#[test]
+fn test_bytes_conversions() {
+    use std::convert::TryFrom;
+    let data = hex!("B4 42   41 00 00 08");
+    let cmd = Command::try_from(&data[..]).unwrap();
+    assert_eq!(cmd, Command::decode(&data).unwrap());
+    let bytes: Vec<u8> = cmd.into();
+    assert_eq!(bytes, data.to_vec());
+    assert_eq!(Command::try_from(&data[..3]), Command::decode(&data[..3]));
+}
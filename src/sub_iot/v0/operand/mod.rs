@@ -1,9 +1,9 @@
 pub use crate::spec::v1_2::operand::{
     ActionStatus, BitmapRangeComparison, ComparisonWithOtherFile, ComparisonWithValue,
-    ComparisonWithZero, FileOffset, IndirectInterface, InterfaceStatus, NonVoid,
-    OverloadedIndirectInterface, Permission, PermissionDecodingError, Query, QueryCode,
-    QueryComparisonType, QueryDecodingError, QueryRangeComparisonType, StatusCode,
-    StringTokenSearch,
+    ComparisonWithZero, FileOffset, IndirectInterface, InterfaceStatus,
+    NonOverloadedIndirectInterface, NonVoid, OverloadedIndirectInterface, Permission,
+    PermissionDecodingError, Query, QueryCode, QueryComparisonType, QueryDecodingError,
+    QueryRangeComparisonType, StatusCode, StringTokenSearch,
 };
 
 pub mod interface_configuration;
@@ -1,5 +1,9 @@
 #[cfg(test)]
 use crate::codec::{Codec, WithSize};
+#[cfg(test)]
+use crate::dialect::{decode_any, AlpDialect};
+#[cfg(test)]
+use std::path::Path;
 
 #[cfg(test)]
 pub fn test_item<T: Codec + std::fmt::Debug + std::cmp::PartialEq>(item: T, data: &[u8])
@@ -8,10 +12,252 @@ where
 {
     assert_eq!(item.encode()[..], *data);
     assert_eq!(
-        T::decode(&data).expect("should be parsed without error"),
+        T::decode(data).expect("should be parsed without error"),
         WithSize {
             value: item,
             size: data.len(),
         }
     );
+    crate::no_panic::check_truncations(|data| T::decode(data).ok().map(|v| v.size), data);
+}
+
+/// File header used by the actions of the test fixtures.
+#[cfg(test)]
+pub fn file_header() -> crate::spec::v1_2::data::FileHeader {
+    use crate::spec::v1_2::data;
+    data::FileHeader {
+        permissions: data::Permissions {
+            encrypted: false,
+            executable: false,
+            user: data::UserPermissions {
+                read: true,
+                write: true,
+                run: false,
+            },
+            guest: data::UserPermissions {
+                read: true,
+                write: false,
+                run: false,
+            },
+        },
+        properties: data::FileProperties {
+            act_en: false,
+            act_cond: data::ActionCondition::Read,
+            storage_class: data::StorageClass::Permanent,
+        },
+        alp_cmd_fid: 0,
+        interface_file_id: 0,
+        file_size: 8,
+        allocated_size: 8,
+    }
+}
+
+/// Defines, in the action test module of a dialect, `minimal_action` building the smallest
+/// action of each opcode, and the `opcode_coverage` test checking the opcode, size, round trip
+/// and Display of each of them.
+///
+/// The actions of the opcodes shared by every dialect are built by the macro, the dialect
+/// specific ones being given as extra match arms, optionally preceded by an attribute of the test
+/// followed by a `;`. There is no wildcard arm on purpose: adding an
+/// opcode must force the table to be updated, and the action to be covered by the test.
+#[cfg(test)]
+macro_rules! opcode_coverage {
+    ($(#[$test_attr: meta];)? $($op_code: pat => $action: expr),* $(,)?) => {
+        fn minimal_action(op_code: OpCode) -> Option<Action> {
+            let header = crate::test_tools::file_header;
+            Some(match op_code {
+                OpCode::Nop => Action::nop(false, false),
+                OpCode::ReadFileData => Action::read_file_data(false, false, 1, 0, 1),
+                OpCode::ReadFileProperties => Action::read_file_properties(false, false, 1),
+                OpCode::WriteFileData => Action::write_file_data(false, false, 1, 0, &[0u8][..]),
+                OpCode::WriteFileProperties => {
+                    Action::write_file_properties(false, false, 1, header())
+                }
+                OpCode::ActionQuery => Action::ActionQuery(QueryAction::in_range(1, 0, 0, 1)),
+                OpCode::BreakQuery => Action::BreakQuery(QueryAction::in_range(1, 0, 0, 1)),
+                OpCode::PermissionRequest => Action::PermissionRequest(PermissionRequest {
+                    group: false,
+                    resp: false,
+                    level: crate::spec::v1_2::operand::permission_level::USER,
+                    permission: operand::Permission::Dash7([0; 8]),
+                }),
+                OpCode::VerifyChecksum => {
+                    Action::VerifyChecksum(QueryAction::in_range(1, 0, 0, 1))
+                }
+                OpCode::ExistFile => Action::test_exist_file(false, false, 1),
+                OpCode::CreateNewFile => Action::create_new_file(false, false, 1, header()),
+                OpCode::DeleteFile => Action::test_delete_file(false, false, 1),
+                OpCode::RestoreFile => Action::test_restore_file(false, false, 1),
+                OpCode::FlushFile => Action::test_flush_file(false, false, 1),
+                OpCode::CopyFile => Action::copy_file(false, false, 1, 2),
+                OpCode::ExecuteFile => Action::test_execute_file(false, false, 1),
+                OpCode::ReturnFileData => {
+                    Action::return_file_data(false, false, 1, 0, &[0u8][..])
+                }
+                OpCode::ReturnFileProperties => {
+                    Action::return_file_properties(false, false, 1, header())
+                }
+                OpCode::Status => Action::Status(Status::Action(operand::ActionStatus {
+                    action_id: 0,
+                    status: operand::StatusCode::Received,
+                })),
+                OpCode::ResponseTag => Action::response_tag(true, false, 1),
+                OpCode::Chunk => Action::chunk(Chunk::StartEnd),
+                OpCode::Logic => Action::logic(Logic::Or),
+                OpCode::Forward => Action::forward(Forward {
+                    resp: false,
+                    conf: operand::InterfaceConfiguration::Host,
+                }),
+                OpCode::IndirectForward => Action::indirect_forward(IndirectForward {
+                    resp: false,
+                    interface: operand::IndirectInterface::Overloaded(
+                        operand::OverloadedIndirectInterface {
+                            interface_file_id: 1,
+                            nls_method: dash7::NlsMethod::None,
+                            access_class: 0,
+                            address: dash7::Address::NoId,
+                        },
+                    ),
+                }),
+                OpCode::RequestTag => Action::request_tag(true, 1),
+                $($op_code => $action,)*
+                OpCode::Extension => return None,
+            })
+        }
+
+        $(#[$test_attr])?
+        #[test]
+        fn opcode_coverage() {
+            for n in 0..0x40 {
+                let op_code = match OpCode::from(n) {
+                    Ok(op_code) => op_code,
+                    Err(_) => continue,
+                };
+                let action = match minimal_action(op_code) {
+                    Some(action) => action,
+                    None => continue,
+                };
+                assert_eq!(action.op_code(), op_code);
+                let encoded = action.encode();
+                assert_eq!(encoded.len(), action.encoded_size(), "{}", op_code);
+                assert_eq!(encoded[0] & 0x3F, n, "{}", op_code);
+                assert_eq!(
+                    Action::decode(&encoded),
+                    Ok(WithSize {
+                        value: action.clone(),
+                        size: encoded.len(),
+                    }),
+                    "{}",
+                    op_code
+                );
+                assert!(!action.to_string().is_empty(), "{}", op_code);
+            }
+        }
+    };
+}
+#[cfg(test)]
+pub(crate) use opcode_coverage;
+
+/// Replays the captures (`.hex` files) of a directory, checking that each of them decodes as a
+/// `dialect` command which encodes back to the captured bytes.
+///
+/// A capture holds the hexadecimal frame, whitespace being ignored, as are the lines starting
+/// with `#`. The result of each file is printed, and the function panics once all of them are
+/// replayed if any failed.
+#[cfg(test)]
+pub fn replay_dir(path: impl AsRef<Path>, dialect: AlpDialect) {
+    let mut paths: Vec<_> = std::fs::read_dir(path.as_ref())
+        .unwrap_or_else(|e| panic!("cannot read {}: {}", path.as_ref().display(), e))
+        .map(|entry| entry.expect("cannot list the directory").path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "hex"))
+        .collect();
+    paths.sort();
+
+    let mut failures = 0;
+    for path in paths.iter() {
+        match replay_file(path, dialect) {
+            Ok(()) => println!("{}: ok", path.display()),
+            Err(e) => {
+                println!("{}: FAILED: {}", path.display(), e);
+                failures += 1;
+            }
+        }
+    }
+    assert!(
+        failures == 0,
+        "{} of {} captures failed",
+        failures,
+        paths.len()
+    );
+}
+
+#[cfg(test)]
+fn replay_file(path: &Path, dialect: AlpDialect) -> Result<(), String> {
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let digits: String = text
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .flat_map(|line| line.chars().filter(|c| !c.is_whitespace()))
+        .collect();
+    let data = hex::decode(digits).map_err(|e| format!("bad hex: {}", e))?;
+    let command = decode_any(dialect, &data)
+        .map_err(|e| format!("decoding failed at offset {}: {:?}", e.offset, e.value))?;
+    let encoded = command.encode();
+    if encoded[..] != data[..] {
+        return Err(format!(
+            "re-encoded as {} instead of {}",
+            hex::encode_upper(&encoded),
+            hex::encode_upper(&data)
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn capture_dir(name: &str, files: &[(&str, &str)]) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("dash7_alp_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        for (file, content) in files {
+            std::fs::write(dir.join(file), content).unwrap();
+        }
+        dir
+    }
+
+    #[cfg(feature = "actions")]
+    #[test]
+    fn test_replay_dir() {
+        let dir = capture_dir(
+            "replay",
+            &[
+                ("read.hex", "# Tagged read\nB4 42\n41 00 00 08\n"),
+                ("nop.hex", "00"),
+                ("notes.txt", "not a capture"),
+            ],
+        );
+        replay_dir(&dir, AlpDialect::SpecV1_2);
+        replay_dir(&dir, AlpDialect::WizzilabV5_3);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_replay_file_failures() {
+        let dir = capture_dir(
+            "replay_failures",
+            &[
+                ("bad_hex.hex", "B4 4"),
+                ("truncated.hex", "41 00"),
+                // Non minimal varint
+                ("varint.hex", "41 00 40 00 08"),
+            ],
+        );
+        for file in ["bad_hex.hex", "truncated.hex", "varint.hex"] {
+            assert!(replay_file(&dir.join(file), AlpDialect::SpecV1_2).is_err());
+        }
+        assert!(std::panic::catch_unwind(|| replay_dir(&dir, AlpDialect::SpecV1_2)).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
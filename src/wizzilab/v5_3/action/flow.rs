@@ -75,10 +75,19 @@ impl Codec for Flow {
     }
 
     fn decode(out: &[u8]) -> Result<WithSize<Self>, WithOffset<Self::Error>> {
-        if out.is_empty() {
-            return Err(WithOffset::new_head(StdError::MissingBytes(1)));
+        if out.len() < 2 {
+            return Err(WithOffset::new_head(StdError::MissingBytes(2 - out.len())));
         }
         let flow_type = FlowType::from(out[0] >> 7);
+        let size = match flow_type {
+            FlowType::U16 => 4,
+            FlowType::U32 => 6,
+        };
+        if out.len() < size {
+            return Err(WithOffset::new_head(StdError::MissingBytes(
+                size - out.len(),
+            )));
+        }
         let flow = out[1];
         let out = &out[2..];
 
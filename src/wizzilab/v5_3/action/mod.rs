@@ -585,6 +585,80 @@ mod test_codec {
     use super::*;
     use crate::spec::v1_2::data;
 
+    crate::test_tools::opcode_coverage! {
+        OpCode::WriteFileDataFlush => Action::WriteFileDataFlush(FileDataAction {
+            group: false,
+            resp: false,
+            file_id: 1,
+            offset: 0,
+            data: Box::new([0]),
+        }),
+        OpCode::TxStatus => Action::TxStatus(TxStatus::Interface(
+            operand::InterfaceTxStatus::D7asp(dash7::interface_tx_status::InterfaceTxStatus {
+                ch_header: 0,
+                ch_idx: 0,
+                eirp: 0,
+                err: dash7::stack_error::InterfaceFinalStatusCode::Busy,
+                rfu_0: 0,
+                rfu_1: 0,
+                rfu_2: 0,
+                lts: 0,
+                access_class: 0,
+                nls_method: dash7::NlsMethod::None,
+                address: dash7::Address::NoId,
+            }),
+        )),
+        OpCode::Flow => Action::Flow(Flow {
+            flow: 0,
+            seqnum: FlowSeqnum::U16(0),
+        }),
+    }
+
+    /// Opcode values of the specification, plus the Wizzilab specific ones.
+    const OPCODE_TABLE: &[(OpCode, u8)] = &[
+        (OpCode::Nop, 0),
+        (OpCode::ReadFileData, 1),
+        (OpCode::ReadFileProperties, 2),
+        (OpCode::WriteFileData, 4),
+        (OpCode::WriteFileDataFlush, 5),
+        (OpCode::WriteFileProperties, 6),
+        (OpCode::ActionQuery, 8),
+        (OpCode::BreakQuery, 9),
+        (OpCode::PermissionRequest, 10),
+        (OpCode::VerifyChecksum, 11),
+        (OpCode::ExistFile, 16),
+        (OpCode::CreateNewFile, 17),
+        (OpCode::DeleteFile, 18),
+        (OpCode::RestoreFile, 19),
+        (OpCode::FlushFile, 20),
+        (OpCode::CopyFile, 23),
+        (OpCode::ExecuteFile, 31),
+        (OpCode::ReturnFileData, 32),
+        (OpCode::ReturnFileProperties, 33),
+        (OpCode::Status, 34),
+        (OpCode::ResponseTag, 35),
+        (OpCode::TxStatus, 38),
+        (OpCode::Chunk, 48),
+        (OpCode::Logic, 49),
+        (OpCode::Forward, 50),
+        (OpCode::IndirectForward, 51),
+        (OpCode::RequestTag, 52),
+        (OpCode::Flow, 54),
+        (OpCode::Extension, 63),
+    ];
+
+    #[test]
+    fn opcode_discriminants() {
+        for (op_code, value) in OPCODE_TABLE.iter().copied() {
+            assert_eq!(op_code as u8, value, "{}", op_code);
+            assert_eq!(OpCode::from(value), Ok(op_code));
+        }
+        assert_eq!(
+            (0..=0xFF).filter(|n| OpCode::from(*n).is_ok()).count(),
+            OPCODE_TABLE.len()
+        );
+    }
+
     #[test]
     fn nop() {
         test_item(
@@ -838,7 +912,18 @@ mod test_codec {
                 ),
             }),
             &hex!("F3   04   37 FF ABCD  000000000000"),
-        )
+        );
+        test_item(
+            Action::IndirectForward(IndirectForward {
+                resp: false,
+                interface: operand::IndirectInterface::NonOverloaded(
+                    operand::NonOverloadedIndirectInterface {
+                        interface_file_id: 4,
+                    },
+                ),
+            }),
+            &hex!("33   04"),
+        );
     }
 
     #[test]
@@ -1379,8 +1464,8 @@ mod test_display {
                         retry: dash7::RetryMode::Oneshot,
                         resp: dash7::RespMode::Any,
                     },
-                    to: 0x23,
-                    te: 0x34,
+                    to: dash7::CompressedTime(0x23),
+                    te: dash7::CompressedTime(0x34),
                     nls_method: dash7::NlsMethod::AesCcm32,
                     access_class: 0xFF,
                     address: dash7::Address::Vid([0xAB, 0xCD]),
@@ -1389,7 +1474,7 @@ mod test_display {
                 }),
             })
             .to_string(),
-            "FWD[R]D7:0X,35,52|0xFF,use_vid=false,NLS[7],GCD=X,VID[ABCD]"
+            "FWD[R]D7:0X,to=12s|0xFF,NLS[7],VID[ABCD],te=80ms,use_vid=false,GCD=ANY"
         );
     }
 
@@ -88,9 +88,9 @@ impl Codec for InterfaceTxStatus {
         i
     }
     fn decode(out: &[u8]) -> Result<WithSize<Self>, WithOffset<Self::Error>> {
-        if out.len() < 15 {
+        if out.len() < 14 {
             return Err(WithOffset::new_head(Self::Error::MissingBytes(
-                15 - out.len(),
+                14 - out.len(),
             )));
         }
 
@@ -112,7 +112,7 @@ impl Codec for InterfaceTxStatus {
         } = Address::parse(address_type, &out[14..]).map_err(|e| {
             let WithOffset { offset, value } = e;
             WithOffset {
-                offset: offset + 5,
+                offset: offset + 14,
                 value: value.into(),
             }
         })?;
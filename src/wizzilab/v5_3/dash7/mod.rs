@@ -4,13 +4,13 @@ pub use crate::{
     codec::{Codec, StdError, WithOffset, WithSize},
     spec::v1_2 as spec,
     spec::v1_2::dash7::{
-        file, AddressType, GroupCondition, InterfaceConfigurationDecodingError, NlsMethod,
-        NlsState, QosDecodingError, RespMode, RetryMode as SpecRetryMode,
+        file, AddressType, CompressedMillis, CompressedSeconds, CompressedTime, DialogId,
+        GroupCondition, InterfaceConfigurationDecodingError, NlsMethod, NlsState, QosDecodingError,
+        RespMode, RetryMode as SpecRetryMode,
     },
 };
 #[cfg(test)]
 use hex_literal::hex;
-use std::convert::TryFrom;
 pub mod interface_tx_status;
 pub mod stack_error;
 
@@ -182,6 +182,32 @@ impl Address {
             Self::Vid(_) => AddressType::Vid,
         }
     }
+
+    /// Estimated number of receivers of a `NbId` address, decoded from its compressed format.
+    pub fn nbid_count(&self) -> Option<u32> {
+        match self {
+            Self::NbId(n) => Some(spec::compressed::decode(*n)),
+            _ => None,
+        }
+    }
+
+    /// `NbId` address targeting approximately `count` receivers.
+    ///
+    /// The count is rounded to the nearest value representable in the compressed format.
+    pub fn nbid_from_count(count: u32) -> Self {
+        Self::NbId(spec::compressed::encode_nearest(count))
+    }
+}
+#[test]
+fn test_address_nbid_count() {
+    assert_eq!(Address::NbId(0x00).nbid_count(), Some(0));
+    assert_eq!(Address::NbId(0x1F).nbid_count(), Some(31));
+    assert_eq!(Address::NbId(0x28).nbid_count(), Some(32));
+    assert_eq!(Address::NbId(0xFF).nbid_count(), Some(507_904));
+    assert_eq!(Address::NoId.nbid_count(), None);
+    assert_eq!(Address::nbid_from_count(10), Address::NbId(0x0A));
+    assert_eq!(Address::nbid_from_count(100), Address::NbId(0x39));
+    assert_eq!(Address::nbid_from_count(1000), Address::NbId(0x70));
 }
 impl std::fmt::Display for Address {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -243,7 +269,7 @@ impl Address {
                 let mut uid = [0u8; 8];
                 uid.copy_from_slice(
                     data.get(..8).ok_or_else(|| {
-                        WithOffset::new_head(StdError::MissingBytes(data.len() - 8))
+                        WithOffset::new_head(StdError::MissingBytes(8 - data.len()))
                     })?,
                 );
                 WithSize {
@@ -252,12 +278,12 @@ impl Address {
                 }
             }
             AddressType::Vid => {
+                // The VID is padded to the size of an UID
+                let data = data
+                    .get(..8)
+                    .ok_or_else(|| WithOffset::new_head(StdError::MissingBytes(8 - data.len())))?;
                 let mut vid = [0u8; 2];
-                vid.copy_from_slice(
-                    data.get(..2).ok_or_else(|| {
-                        WithOffset::new_head(StdError::MissingBytes(data.len() - 2))
-                    })?,
-                );
+                vid.copy_from_slice(&data[..2]);
                 WithSize {
                     size: 8,
                     value: Self::Vid(vid),
@@ -266,6 +292,27 @@ impl Address {
         })
     }
 }
+#[test]
+fn test_address_parse_truncated() {
+    let data = [0xAB; 8];
+    for (ty, size) in [
+        (AddressType::NoId, 0),
+        (AddressType::NbId, 1),
+        (AddressType::Uid, 8),
+        (AddressType::Vid, 8),
+    ] {
+        for len in 0..size {
+            assert_eq!(
+                Address::parse(ty, &data[..len]),
+                Err(WithOffset::new_head(StdError::MissingBytes(size - len))),
+                "{:?} from {} bytes",
+                ty,
+                len
+            );
+        }
+        assert_eq!(Address::parse(ty, &data[..size]).unwrap().size, size);
+    }
+}
 
 /// Section 9.2.1
 ///
@@ -284,11 +331,11 @@ pub struct InterfaceConfiguration {
     /// then we can aggregate the requests, to avoid advertising twice. Another example would be if
     /// the target sends us a packet, the modem can aggregate our request to the response of the
     /// request of the target.
-    pub to: u8,
+    pub to: CompressedSeconds,
     /// Response Execution Delay in Compressed Format, unit is in milliseconds.
     ///
     /// Time given to the target to process the request.
-    pub te: u8,
+    pub te: CompressedMillis,
     /// Access class of the targeted listening device
     pub access_class: u8,
     /// Security method
@@ -305,17 +352,18 @@ pub struct InterfaceConfiguration {
 
 impl std::fmt::Display for InterfaceConfiguration {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(
+        spec::dash7::fmt_interface_configuration_common(
             f,
-            "{},{},{}|0x{},use_vid={},{},{},{}",
-            self.qos,
+            &self.qos,
             self.to,
-            self.te,
-            hex::encode_upper([self.access_class]),
-            self.use_vid,
+            self.access_class.into(),
             self.nls_method,
-            self.group_condition,
-            self.address
+            &self.address,
+        )?;
+        write!(
+            f,
+            ",te={},use_vid={},{}",
+            self.te, self.use_vid, self.group_condition
         )
     }
 }
@@ -327,8 +375,8 @@ impl Codec for InterfaceConfiguration {
     }
     unsafe fn encode_in(&self, out: &mut [u8]) -> usize {
         self.qos.encode_in(out);
-        out[1] = self.to;
-        out[2] = self.te;
+        out[1] = self.to.0;
+        out[2] = self.te.0;
         out[3] = ((self.group_condition as u8) << 6)
             | ((self.address.id_type() as u8) << 4)
             | ((self.use_vid as u8) << 3)
@@ -346,9 +394,9 @@ impl Codec for InterfaceConfiguration {
             value: qos,
             size: qos_size,
         } = Qos::decode(out).map_err(|e| e.map_value(Self::Error::Qos))?;
-        let to = out[1];
-        let te = out[2];
-        let group_condition = GroupCondition::try_from((out[3] >> 6) & 0x03).unwrap();
+        let to = CompressedTime(out[1]);
+        let te = CompressedTime(out[2]);
+        let group_condition = GroupCondition::from_bits(out[3] >> 6);
         let address_type = AddressType::from((out[3] & 0x30) >> 4);
         let use_vid = (out[3] & 0x08) != 0;
         let nls_method = unsafe { NlsMethod::from(out[3] & 0x07) };
@@ -386,8 +434,8 @@ fn test_interface_configuration() {
                 retry: RetryMode::Oneshot,
                 resp: RespMode::Any,
             },
-            to: 0x23,
-            te: 0x34,
+            to: CompressedTime(0x23),
+            te: CompressedTime(0x34),
             nls_method: NlsMethod::AesCcm32,
             access_class: 0xFF,
             address: Address::Vid([0xAB, 0xCD]),
@@ -406,8 +454,8 @@ fn test_interface_configuration_with_address_nbid() {
                 retry: RetryMode::Oneshot,
                 resp: RespMode::Any,
             },
-            to: 0x23,
-            te: 0x34,
+            to: CompressedTime(0x23),
+            te: CompressedTime(0x34),
             nls_method: NlsMethod::None,
             access_class: 0x00,
             address: Address::NbId(0x15),
@@ -425,8 +473,8 @@ fn test_interface_configuration_with_address_noid() {
                 retry: RetryMode::Oneshot,
                 resp: RespMode::Any,
             },
-            to: 0x23,
-            te: 0x34,
+            to: CompressedTime(0x23),
+            te: CompressedTime(0x34),
             nls_method: NlsMethod::AesCbcMac128,
             access_class: 0x24,
             address: Address::NoId,
@@ -444,8 +492,8 @@ fn test_interface_configuration_with_address_uid() {
                 retry: RetryMode::Oneshot,
                 resp: RespMode::Any,
             },
-            to: 0x23,
-            te: 0x34,
+            to: CompressedTime(0x23),
+            te: CompressedTime(0x34),
             nls_method: NlsMethod::AesCcm64,
             access_class: 0x48,
             address: Address::Uid([0, 1, 2, 3, 4, 5, 6, 7]),
@@ -463,8 +511,8 @@ fn test_interface_configuration_with_address_vid() {
                 retry: RetryMode::Oneshot,
                 resp: RespMode::Any,
             },
-            to: 0x23,
-            te: 0x34,
+            to: CompressedTime(0x23),
+            te: CompressedTime(0x34),
             nls_method: NlsMethod::AesCcm32,
             access_class: 0xFF,
             address: Address::Vid([0xAB, 0xCD]),
@@ -491,7 +539,7 @@ impl From<spec::dash7::InterfaceConfiguration> for InterfaceConfiguration {
             to,
             te,
             nls_method,
-            access_class,
+            access_class: access_class.into(),
             address: address.into(),
             use_vid,
             group_condition,
@@ -515,7 +563,7 @@ impl From<InterfaceConfiguration> for spec::dash7::InterfaceConfiguration {
             to,
             te,
             nls_method,
-            access_class,
+            access_class: access_class.into(),
             address: address.into(),
             use_vid,
             group_condition,
@@ -575,6 +623,27 @@ impl std::fmt::Display for InterfaceStatus {
         )
     }
 }
+impl InterfaceStatus {
+    /// Received signal strength in dBm (`rxlev` holds its opposite: a `rxlev` of `2` is -2 dBm)
+    pub fn rssi_dbm(&self) -> i16 {
+        -(self.rxlev as i16)
+    }
+    /// Link budget in dB
+    pub fn link_budget_db(&self) -> i16 {
+        self.lb as i16
+    }
+    /// Signal-to-noise ratio in dB
+    pub fn snr_db(&self) -> i16 {
+        self.snr as i16
+    }
+    /// Dialog and transaction the received packet belongs to
+    pub fn dialog_id(&self) -> DialogId {
+        DialogId {
+            token: self.token,
+            seq: self.seq,
+        }
+    }
+}
 impl Codec for InterfaceStatus {
     type Error = StdError;
     fn encoded_size(&self) -> usize {
@@ -619,9 +688,9 @@ impl Codec for InterfaceStatus {
         i
     }
     fn decode(out: &[u8]) -> Result<WithSize<Self>, WithOffset<Self::Error>> {
-        if out.len() < 10 {
+        if out.len() < 15 {
             return Err(WithOffset::new_head(Self::Error::MissingBytes(
-                10 - out.len(),
+                15 - out.len(),
             )));
         }
         let ch_header = out[0];
@@ -704,6 +773,40 @@ fn test_interface_status() {
     )
 }
 
+#[test]
+fn test_interface_status_truncated() {
+    let status = InterfaceStatus {
+        ch_header: 1,
+        ch_idx: 0x0123,
+        rxlev: 2,
+        lb: 3,
+        snr: 4,
+        status: 5,
+        token: 6,
+        seq: 7,
+        resp_to: 8,
+        fof: 9,
+        access_class: 0xFF,
+        address: Address::Vid([0xAB, 0xCD]),
+        nls_state: NlsState::AesCcm32(hex!("00 11 22 33 44")),
+    };
+    let data = status.encode();
+    // Every truncation, including inside the NLS state, is reported instead of read past
+    for size in 0..data.len() {
+        match InterfaceStatus::decode(&data[..size]) {
+            Err(WithOffset {
+                value: StdError::MissingBytes(n),
+                ..
+            }) => assert!(n > 0, "{}", size),
+            other => panic!("{}: {:?}", size, other),
+        }
+    }
+    assert_eq!(
+        InterfaceStatus::decode(&data[..data.len() - 2]),
+        Err(WithOffset::new(data.len() - 5, StdError::MissingBytes(2)))
+    );
+}
+
 impl From<spec::dash7::InterfaceStatus> for InterfaceStatus {
     fn from(status: spec::dash7::InterfaceStatus) -> Self {
         Self {
@@ -717,7 +820,7 @@ impl From<spec::dash7::InterfaceStatus> for InterfaceStatus {
             seq: status.seq,
             resp_to: status.resp_to,
             fof: status.fof,
-            access_class: status.access_class,
+            access_class: status.access_class.into(),
             address: status.address.into(),
             nls_state: status.nls_state,
         }
@@ -737,7 +840,7 @@ impl From<InterfaceStatus> for spec::dash7::InterfaceStatus {
             seq: status.seq,
             resp_to: status.resp_to,
             fof: status.fof,
-            access_class: status.access_class,
+            access_class: status.access_class.into(),
             address: status.address.into(),
             nls_state: status.nls_state,
         }
@@ -9,6 +9,7 @@ pub mod dash7;
 pub mod operand;
 /// ALP variable int codec implementation
 pub use crate::codec::{Codec, WithOffset, WithSize};
+use crate::spec::v1_2 as spec;
 pub use crate::spec::v1_2::varint;
 pub use action::Action;
 
@@ -25,16 +26,26 @@ pub struct Command {
 impl std::fmt::Display for Command {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "[")?;
-        let end = self.actions.len() - 1;
         for (i, action) in self.actions.iter().enumerate() {
-            write!(f, "{}", action)?;
-            if i != end {
+            if i != 0 {
                 write!(f, "; ")?;
             }
+            write!(f, "{}", action)?;
         }
         write!(f, "]")
     }
 }
+impl From<Command> for Vec<u8> {
+    fn from(command: Command) -> Self {
+        command.encode().into_vec()
+    }
+}
+impl std::convert::TryFrom<&[u8]> for Command {
+    type Error = WithOffset<CommandParseFail>;
+    fn try_from(out: &[u8]) -> Result<Self, Self::Error> {
+        Self::decode(out)
+    }
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct CommandParseFail {
@@ -42,6 +53,18 @@ pub struct CommandParseFail {
     pub error: action::ActionDecodingError,
 }
 
+/// Action without spec equivalent found while converting a command (see
+/// [to_spec_lossy](struct.Command.html#method.to_spec_lossy)).
+#[derive(Debug, Clone, PartialEq)]
+pub struct DroppedAction {
+    /// Index of the action in the converted command
+    pub index: usize,
+    pub action: Action,
+    /// Whether the action was replaced by spec actions having the same effect instead of being
+    /// dropped
+    pub rewritten: bool,
+}
+
 impl Command {
     pub fn encoded_size(&self) -> usize {
         self.actions.iter().map(|act| act.encoded_size()).sum()
@@ -91,31 +114,160 @@ impl Command {
         Ok(Self { actions })
     }
 
+    /// First request tag of the command, if any
+    pub fn request_tag(&self) -> Option<&action::RequestTag> {
+        self.actions.iter().find_map(|action| match action {
+            Action::RequestTag(tag) => Some(tag),
+            _ => None,
+        })
+    }
+
+    /// First response tag of the command, if any
+    pub fn response_tag(&self) -> Option<&action::ResponseTag> {
+        self.actions.iter().find_map(|action| match action {
+            Action::ResponseTag(tag) => Some(tag),
+            _ => None,
+        })
+    }
+
+    /// Payload of the first interface [Status](action::Status) of the command, if any: the
+    /// reception conditions of a response.
+    pub fn interface_status(&self) -> Option<&operand::InterfaceStatus> {
+        self.actions.iter().find_map(|action| match action {
+            Action::Status(action::Status::Interface(status)) => Some(status),
+            _ => None,
+        })
+    }
+
     pub fn request_id(&self) -> Option<u8> {
-        for action in self.actions.iter() {
-            if let Action::RequestTag(action::RequestTag { id, .. }) = action {
-                return Some(*id);
-            }
-        }
-        None
+        self.request_tag().map(|tag| tag.id)
     }
 
     pub fn response_id(&self) -> Option<u8> {
-        for action in self.actions.iter() {
-            if let Action::ResponseTag(action::ResponseTag { id, .. }) = action {
-                return Some(*id);
-            }
-        }
-        None
+        self.response_tag().map(|tag| tag.id)
+    }
+
+    /// Identifiers of every request tag of the command, in order.
+    ///
+    /// A well formed command has at most one.
+    pub fn all_request_ids(&self) -> Vec<u8> {
+        self.actions
+            .iter()
+            .filter_map(|action| match action {
+                Action::RequestTag(tag) => Some(tag.id),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Identifiers of every response tag of the command, in order.
+    pub fn all_response_ids(&self) -> Vec<u8> {
+        self.actions
+            .iter()
+            .filter_map(|action| match action {
+                Action::ResponseTag(tag) => Some(tag.id),
+                _ => None,
+            })
+            .collect()
     }
 
     pub fn is_last_response(&self) -> bool {
-        for action in self.actions.iter() {
-            if let Action::ResponseTag(action::ResponseTag { eop, .. }) = action {
-                return *eop;
-            }
+        self.response_tag().map(|tag| tag.eop).unwrap_or(false)
+    }
+
+    /// Converts the command to a spec command, reporting the actions that cannot be expressed in
+    /// the spec.
+    ///
+    /// The actions shared with the spec are converted. A
+    /// [WriteFileDataFlush](Action::WriteFileDataFlush) is rewritten as a
+    /// [WriteFileData](spec::Action::WriteFileData) grouped with a following
+    /// [FlushFile](spec::Action::FlushFile) of the same file, the flush carrying the `group` and
+    /// `resp` flags of the original action. The
+    /// [Flow](Action::Flow) and [TxStatus](Action::TxStatus) actions, the interface final
+    /// statuses and the action statuses with a wizzilab only status code are dropped.
+    pub fn to_spec_lossy(&self) -> (spec::Command, Vec<DroppedAction>) {
+        let mut actions = Vec::with_capacity(self.actions.len());
+        let mut dropped = vec![];
+        for (index, action) in self.actions.iter().enumerate() {
+            let converted = match action.clone() {
+                Action::Nop(a) => spec::Action::Nop(a),
+                Action::ReadFileData(a) => spec::Action::ReadFileData(a),
+                Action::ReadFileProperties(a) => spec::Action::ReadFileProperties(a),
+                Action::WriteFileData(a) => spec::Action::WriteFileData(a),
+                Action::WriteFileDataFlush(a) => {
+                    let flush = action::FileIdAction {
+                        group: a.group,
+                        resp: a.resp,
+                        file_id: a.file_id,
+                    };
+                    actions.push(spec::Action::WriteFileData(action::FileDataAction {
+                        group: true,
+                        resp: false,
+                        ..a
+                    }));
+                    actions.push(spec::Action::FlushFile(flush));
+                    dropped.push(DroppedAction {
+                        index,
+                        action: action.clone(),
+                        rewritten: true,
+                    });
+                    continue;
+                }
+                Action::WriteFileProperties(a) => spec::Action::WriteFileProperties(a),
+                Action::ActionQuery(a) => spec::Action::ActionQuery(a),
+                Action::BreakQuery(a) => spec::Action::BreakQuery(a),
+                Action::PermissionRequest(a) => spec::Action::PermissionRequest(a),
+                Action::VerifyChecksum(a) => spec::Action::VerifyChecksum(a),
+                Action::ExistFile(a) => spec::Action::ExistFile(a),
+                Action::CreateNewFile(a) => spec::Action::CreateNewFile(a),
+                Action::DeleteFile(a) => spec::Action::DeleteFile(a),
+                Action::RestoreFile(a) => spec::Action::RestoreFile(a),
+                Action::FlushFile(a) => spec::Action::FlushFile(a),
+                Action::CopyFile(a) => spec::Action::CopyFile(a),
+                Action::ExecuteFile(a) => spec::Action::ExecuteFile(a),
+                Action::ReturnFileData(a) => spec::Action::ReturnFileData(a),
+                Action::ReturnFileProperties(a) => spec::Action::ReturnFileProperties(a),
+                Action::ResponseTag(a) => spec::Action::ResponseTag(a),
+                Action::Chunk(a) => spec::Action::Chunk(a),
+                Action::Logic(a) => spec::Action::Logic(a),
+                Action::Forward(a) => spec::Action::Forward(a.into()),
+                Action::IndirectForward(a) => spec::Action::IndirectForward(a.into()),
+                Action::RequestTag(a) => spec::Action::RequestTag(a),
+                Action::Status(action::Status::Interface(status)) => {
+                    spec::Action::Status(spec::action::status::Status::Interface(status.into()))
+                }
+                Action::Status(action::Status::Action(status)) => {
+                    match std::convert::TryFrom::try_from(status.status as u8) {
+                        Ok(code) => spec::Action::Status(spec::action::status::Status::Action(
+                            spec::operand::ActionStatus {
+                                action_id: status.action_id,
+                                status: code,
+                            },
+                        )),
+                        Err(_) => {
+                            dropped.push(DroppedAction {
+                                index,
+                                action: action.clone(),
+                                rewritten: false,
+                            });
+                            continue;
+                        }
+                    }
+                }
+                Action::Status(action::Status::InterfaceFinal(_))
+                | Action::TxStatus(_)
+                | Action::Flow(_) => {
+                    dropped.push(DroppedAction {
+                        index,
+                        action: action.clone(),
+                        rewritten: false,
+                    });
+                    continue;
+                }
+            };
+            actions.push(converted);
         }
-        false
+        (spec::Command { actions }, dropped)
     }
 }
 #[test]
@@ -168,6 +320,15 @@ fn test_command_display() {
     );
 }
 
+#[test]
+fn test_empty_command() {
+    let cmd = Command::default();
+    assert_eq!(cmd.to_string(), "[]");
+    assert_eq!(cmd.encoded_size(), 0);
+    assert!(cmd.encode().is_empty());
+    assert_eq!(Command::decode(&[]), Ok(cmd));
+}
+
 #[test]
 fn test_command_request_id() {
     assert_eq!(
@@ -193,6 +354,38 @@ fn test_command_request_id() {
     );
 }
 
+#[test]
+fn test_interface_status() {
+    let response = Command::decode(&hex!(
+        "A3 08   62 D7 1C  01 0123 4E 5A 0C 05 06 07 0800 0900  37 FF ABCD 000000000000  0011223344"
+    ))
+    .unwrap();
+    let status = match response.interface_status() {
+        Some(operand::InterfaceStatus::D7asp(status)) => status,
+        other => panic!("{:?}", other),
+    };
+    assert_eq!(status.rssi_dbm(), -78);
+    assert_eq!(status.link_budget_db(), 90);
+    assert_eq!(status.snr_db(), 12);
+    assert_eq!(status.dialog_id().to_string(), "dlg(6:7)");
+}
+
+#[test]
+fn test_command_all_ids() {
+    let cmd = Command {
+        actions: vec![
+            Action::request_tag(false, 44),
+            Action::nop(true, false),
+            Action::response_tag(true, false, 66),
+            Action::request_tag(true, 45),
+        ],
+    };
+    assert_eq!(cmd.request_id(), Some(44));
+    assert_eq!(cmd.all_request_ids(), vec![44, 45]);
+    assert_eq!(cmd.all_response_ids(), vec![66]);
+    assert!(Command::default().all_request_ids().is_empty());
+}
+
 #[test]
 fn test_comman_response_id() {
     assert_eq!(
@@ -252,3 +445,118 @@ fn test_command_is_last_response() {
     }
     .is_last_response());
 }
+
+#[test]
+fn test_bytes_conversions() {
+    use std::convert::TryFrom;
+    let data = hex!("B4 42   41 00 00 08");
+    let cmd = Command::try_from(&data[..]).unwrap();
+    assert_eq!(cmd, Command::decode(&data).unwrap());
+    let bytes: Vec<u8> = cmd.into();
+    assert_eq!(bytes, data.to_vec());
+    assert_eq!(Command::try_from(&data[..3]), Command::decode(&data[..3]));
+}
+
+#[cfg(feature = "actions")]
+#[test]
+fn test_to_spec_lossy() {
+    let flow = Action::Flow(action::Flow {
+        flow: 3,
+        seqnum: action::FlowSeqnum::U16(0x0102),
+    });
+    let itf_full = Action::Status(action::Status::Action(operand::ActionStatus {
+        action_id: 2,
+        status: operand::StatusCode::ItfFull,
+    }));
+    let cmd = Command {
+        actions: vec![
+            Action::request_tag(true, 1),
+            Action::WriteFileDataFlush(action::FileDataAction {
+                group: true,
+                resp: true,
+                file_id: 9,
+                offset: 4,
+                data: Box::new([0xAB, 0xCD]),
+            }),
+            flow.clone(),
+            Action::Status(action::Status::Action(operand::ActionStatus {
+                action_id: 1,
+                status: operand::StatusCode::Ok,
+            })),
+            itf_full.clone(),
+            Action::read_file_data(false, true, 0, 0, 8),
+        ],
+    };
+    let (spec_cmd, dropped) = cmd.to_spec_lossy();
+    assert_eq!(
+        spec_cmd.actions,
+        vec![
+            spec::Action::request_tag(true, 1),
+            spec::Action::write_file_data(true, false, 9, 4, &[0xAB, 0xCD][..]),
+            spec::Action::FlushFile(action::FileIdAction {
+                group: true,
+                resp: true,
+                file_id: 9,
+            }),
+            spec::Action::Status(spec::action::status::Status::Action(
+                spec::operand::ActionStatus {
+                    action_id: 1,
+                    status: spec::operand::StatusCode::Ok,
+                }
+            )),
+            spec::Action::read_file_data(false, true, 0, 0, 8),
+        ]
+    );
+    assert_eq!(
+        dropped,
+        vec![
+            DroppedAction {
+                index: 1,
+                action: cmd.actions[1].clone(),
+                rewritten: true,
+            },
+            DroppedAction {
+                index: 2,
+                action: flow,
+                rewritten: false,
+            },
+            DroppedAction {
+                index: 4,
+                action: itf_full,
+                rewritten: false,
+            },
+        ]
+    );
+
+    let data = hex!("B4 42   41 00 00 08");
+    let (spec_cmd, dropped) = Command::decode(&data).unwrap().to_spec_lossy();
+    assert_eq!(spec_cmd, spec::Command::decode(&data).unwrap());
+    assert!(dropped.is_empty());
+}
+
+#[test]
+fn test_to_spec_lossy_write_flush_group() {
+    // The write is always grouped with its flush, which keeps the group flag of the original
+    for group in [false, true] {
+        let cmd = Command {
+            actions: vec![Action::WriteFileDataFlush(action::FileDataAction {
+                group,
+                resp: false,
+                file_id: 9,
+                offset: 0,
+                data: Box::new([0xAB]),
+            })],
+        };
+        assert_eq!(
+            cmd.to_spec_lossy().0.actions,
+            vec![
+                spec::Action::write_file_data(true, false, 9, 0, &[0xAB][..]),
+                spec::Action::FlushFile(action::FileIdAction {
+                    group,
+                    resp: false,
+                    file_id: 9,
+                }),
+            ]
+        );
+    }
+}
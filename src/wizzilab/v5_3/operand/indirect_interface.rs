@@ -47,7 +47,7 @@ impl Codec for OverloadedIndirectInterface {
         }
         let interface_file_id = out[0];
         let address_type = dash7::AddressType::from((out[1] & 0x30) >> 4);
-        let nls_method = unsafe { dash7::NlsMethod::from(out[1] & 0x0F) };
+        let nls_method = unsafe { dash7::NlsMethod::from(out[1] & 0x07) };
         let access_class = out[2];
         let WithSize {
             value: address,
@@ -76,6 +76,28 @@ fn test_overloaded_indirect_interface() {
         &hex!("04   37 FF ABCD 000000000000"),
     )
 }
+#[test]
+fn test_overloaded_indirect_interface_address_types() {
+    let interface = |address| OverloadedIndirectInterface {
+        interface_file_id: 4,
+        nls_method: dash7::NlsMethod::AesCcm32,
+        access_class: 0xFF,
+        address,
+    };
+    test_item(
+        interface(dash7::Address::NbId(0x15)),
+        &hex!("04   07 FF 15"),
+    );
+    test_item(interface(dash7::Address::NoId), &hex!("04   17 FF"));
+    test_item(
+        interface(dash7::Address::Uid(hex!("0001020304050607"))),
+        &hex!("04   27 FF 0001020304050607"),
+    );
+    assert_eq!(
+        OverloadedIndirectInterface::decode(&hex!("04   27 FF 000102")),
+        Err(WithOffset::new(3, StdError::MissingBytes(5)))
+    );
+}
 
 impl From<spec::operand::OverloadedIndirectInterface> for OverloadedIndirectInterface {
     fn from(v: spec::operand::OverloadedIndirectInterface) -> Self {
@@ -99,48 +121,57 @@ impl From<OverloadedIndirectInterface> for spec::operand::OverloadedIndirectInte
     }
 }
 
-/// Non Dash7 interface
+/// Interface fully described by an interface file: the interface configuration, addressee
+/// included, is read from the file.
 #[derive(Clone, Debug, PartialEq)]
-// ALP SPEC: This seems undoable if we do not know the interface (per protocol specific support)
-//  which is still a pretty legitimate policy on a low power protocol.
 pub struct NonOverloadedIndirectInterface {
     pub interface_file_id: u8,
-    // ALP SPEC: Where is this defined? Is this ID specific?
-    pub data: Box<[u8]>,
 }
 
 impl Codec for NonOverloadedIndirectInterface {
     type Error = StdError;
     fn encoded_size(&self) -> usize {
-        1 + self.data.len()
+        1
     }
     unsafe fn encode_in(&self, out: &mut [u8]) -> usize {
         out[0] = self.interface_file_id;
-        let mut offset = 1;
-        out[offset..offset + self.data.len()].clone_from_slice(&self.data);
-        offset += self.data.len();
-        offset
+        1
     }
-    fn decode(_out: &[u8]) -> Result<WithSize<Self>, WithOffset<Self::Error>> {
-        todo!("TODO")
+    fn decode(out: &[u8]) -> Result<WithSize<Self>, WithOffset<Self::Error>> {
+        if out.is_empty() {
+            return Err(WithOffset::new_head(Self::Error::MissingBytes(1)));
+        }
+        Ok(WithSize {
+            value: Self {
+                interface_file_id: out[0],
+            },
+            size: 1,
+        })
     }
 }
 impl std::fmt::Display for NonOverloadedIndirectInterface {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(
-            f,
-            "{},0x{}",
-            self.interface_file_id,
-            hex::encode_upper(&self.data)
-        )
+        write!(f, "{}", self.interface_file_id)
     }
 }
+#[test]
+fn test_non_overloaded_indirect_interface() {
+    test_item(
+        NonOverloadedIndirectInterface {
+            interface_file_id: 4,
+        },
+        &hex!("04"),
+    );
+    assert_eq!(
+        NonOverloadedIndirectInterface::decode(&[]),
+        Err(WithOffset::new_head(StdError::MissingBytes(1)))
+    );
+}
 
 impl From<spec::operand::NonOverloadedIndirectInterface> for NonOverloadedIndirectInterface {
     fn from(v: spec::operand::NonOverloadedIndirectInterface) -> Self {
         Self {
             interface_file_id: v.interface_file_id,
-            data: v.data,
         }
     }
 }
@@ -149,7 +180,6 @@ impl From<NonOverloadedIndirectInterface> for spec::operand::NonOverloadedIndire
     fn from(v: NonOverloadedIndirectInterface) -> Self {
         Self {
             interface_file_id: v.interface_file_id,
-            data: v.data,
         }
     }
 }
@@ -1,4 +1,7 @@
-pub use crate::spec::v1_2::operand::interface_configuration::InterfaceConfigurationDecodingError;
+use crate::spec::v1_2::operand::interface_configuration as custom;
+pub use crate::spec::v1_2::operand::interface_configuration::{
+    InterfaceConfigurationDecodingError, InterfaceId,
+};
 #[cfg(test)]
 use crate::test_tools::test_item;
 use crate::{
@@ -8,24 +11,22 @@ use crate::{
 };
 #[cfg(test)]
 use hex_literal::hex;
-
-#[derive(Clone, Copy, Debug, PartialEq)]
-pub enum InterfaceId {
-    Host = 0,
-    D7asp = 0xD7,
-}
+use std::convert::TryFrom;
 
 /// Meta data required to send a packet depending on the sending interface type
 #[derive(Clone, Debug, PartialEq)]
 pub enum InterfaceConfiguration {
     Host,
     D7asp(dash7::InterfaceConfiguration),
+    /// Configuration of a [registered](custom::register_custom_interface) custom interface
+    Custom(Box<dyn custom::CustomInterface>),
 }
 impl std::fmt::Display for InterfaceConfiguration {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             Self::Host => write!(f, "HOST"),
             Self::D7asp(conf) => write!(f, "D7:{}", conf),
+            Self::Custom(conf) => write!(f, "0x{:02X}:{}", conf.interface_id(), conf),
         }
     }
 }
@@ -35,6 +36,7 @@ impl Codec for InterfaceConfiguration {
         1 + match self {
             InterfaceConfiguration::Host => 0,
             InterfaceConfiguration::D7asp(v) => v.encoded_size(),
+            InterfaceConfiguration::Custom(v) => v.encoded_size(),
         }
     }
     unsafe fn encode_in(&self, out: &mut [u8]) -> usize {
@@ -47,20 +49,22 @@ impl Codec for InterfaceConfiguration {
                 out[0] = InterfaceId::D7asp as u8;
                 1 + v.encode_in(&mut out[1..])
             }
+            InterfaceConfiguration::Custom(v) => {
+                out[0] = v.interface_id();
+                1 + v.encode_in(&mut out[1..])
+            }
         }
     }
     fn decode(out: &[u8]) -> Result<WithSize<Self>, WithOffset<Self::Error>> {
         if out.is_empty() {
             return Err(WithOffset::new_head(Self::Error::MissingBytes(1)));
         }
-        const HOST: u8 = InterfaceId::Host as u8;
-        const D7ASP: u8 = InterfaceId::D7asp as u8;
-        Ok(match out[0] {
-            HOST => WithSize {
+        Ok(match InterfaceId::try_from(out[0]) {
+            Ok(InterfaceId::Host) => WithSize {
                 value: InterfaceConfiguration::Host,
                 size: 1,
             },
-            D7ASP => {
+            Ok(InterfaceId::D7asp) => {
                 let WithSize { value, size } = dash7::InterfaceConfiguration::decode(&out[1..])
                     .map_err(|e| e.map_value(InterfaceConfigurationDecodingError::D7asp))?;
                 WithSize {
@@ -68,11 +72,15 @@ impl Codec for InterfaceConfiguration {
                     size: size + 1,
                 }
             }
-            id => {
+            Err(id) => {
+                #[cfg(feature = "dynamic_interfaces")]
+                if let Some(result) = custom::decode_custom_interface(out) {
+                    return result.map(|v| v.map_value(InterfaceConfiguration::Custom));
+                }
                 return Err(WithOffset {
                     value: Self::Error::BadInterfaceId(id),
                     offset: 0,
-                })
+                });
             }
         })
     }
@@ -85,8 +93,8 @@ fn test_interface_configuration_d7asp() {
                 retry: dash7::RetryMode::Oneshot,
                 resp: dash7::RespMode::Any,
             },
-            to: 0x23,
-            te: 0x34,
+            to: dash7::CompressedTime(0x23),
+            te: dash7::CompressedTime(0x34),
             nls_method: dash7::NlsMethod::AesCcm32,
             access_class: 0xFF,
             address: dash7::Address::Vid([0xAB, 0xCD]),
@@ -105,6 +113,7 @@ impl From<spec::operand::InterfaceConfiguration> for InterfaceConfiguration {
         match o {
             spec::operand::InterfaceConfiguration::Host => Self::Host,
             spec::operand::InterfaceConfiguration::D7asp(conf) => Self::D7asp(conf.into()),
+            spec::operand::InterfaceConfiguration::Custom(conf) => Self::Custom(conf),
         }
     }
 }
@@ -113,6 +122,7 @@ impl From<InterfaceConfiguration> for spec::operand::InterfaceConfiguration {
         match o {
             InterfaceConfiguration::Host => Self::Host,
             InterfaceConfiguration::D7asp(conf) => Self::D7asp(conf.into()),
+            InterfaceConfiguration::Custom(conf) => Self::Custom(conf),
         }
     }
 }
@@ -8,6 +8,7 @@ use crate::{
 #[cfg(test)]
 use hex_literal::hex;
 pub use spec::operand::InterfaceStatusUnknown;
+use std::convert::TryFrom;
 
 // TODO Allow padding at the end
 // We should support the parsing and the encoding of this padding
@@ -79,15 +80,18 @@ impl Codec for InterfaceStatus {
         if out.is_empty() {
             return Err(WithOffset::new_head(Self::Error::MissingBytes(1)));
         }
-        const HOST: u8 = InterfaceId::Host as u8;
-        const D7ASP: u8 = InterfaceId::D7asp as u8;
         let mut offset = 1;
-        let value = match out[0] {
-            HOST => {
+        let value = match InterfaceId::try_from(out[0]) {
+            Ok(InterfaceId::Host) => {
+                if out.len() < 2 {
+                    return Err(WithOffset::new_head(Self::Error::MissingBytes(
+                        2 - out.len(),
+                    )));
+                }
                 offset += 1;
                 InterfaceStatus::Host
             }
-            D7ASP => {
+            Ok(InterfaceId::D7asp) => {
                 let WithSize {
                     value: size,
                     size: size_size,
@@ -100,6 +104,11 @@ impl Codec for InterfaceStatus {
                 })?;
                 let announced_size = size as usize;
                 offset += size_size;
+                if out.len() < offset + announced_size {
+                    return Err(WithOffset::new_head(Self::Error::MissingBytes(
+                        offset + announced_size - out.len(),
+                    )));
+                }
                 let WithSize { value, size } = dash7::InterfaceStatus::decode(
                     &out[offset..offset + announced_size],
                 )
@@ -113,7 +122,7 @@ impl Codec for InterfaceStatus {
                 offset += size.max(announced_size);
                 InterfaceStatus::D7asp(value)
             }
-            id => {
+            Err(id) => {
                 let WithSize {
                     value: size,
                     size: size_size,
@@ -13,6 +13,7 @@ use crate::{
 };
 #[cfg(test)]
 use hex_literal::hex;
+use std::convert::TryFrom;
 
 // TODO Allow padding at the end
 // We should support the parsing and the encoding of this padding
@@ -103,15 +104,18 @@ impl Codec for InterfaceTxStatus {
         if out.is_empty() {
             return Err(WithOffset::new_head(Self::Error::MissingBytes(1)));
         }
-        const HOST: u8 = InterfaceId::Host as u8;
-        const D7ASP: u8 = InterfaceId::D7asp as u8;
         let mut offset = 1;
-        let value = match out[0] {
-            HOST => {
+        let value = match InterfaceId::try_from(out[0]) {
+            Ok(InterfaceId::Host) => {
+                if out.len() < 2 {
+                    return Err(WithOffset::new_head(Self::Error::MissingBytes(
+                        2 - out.len(),
+                    )));
+                }
                 offset += 1;
                 InterfaceTxStatus::Host
             }
-            D7ASP => {
+            Ok(InterfaceId::D7asp) => {
                 let WithSize {
                     value: size,
                     size: size_size,
@@ -124,6 +128,11 @@ impl Codec for InterfaceTxStatus {
                 })?;
                 let announced_size = size as usize;
                 offset += size_size;
+                if out.len() < offset + announced_size {
+                    return Err(WithOffset::new_head(Self::Error::MissingBytes(
+                        offset + announced_size - out.len(),
+                    )));
+                }
                 let WithSize { value, size } =
                     dash7::interface_tx_status::InterfaceTxStatus::decode(
                         &out[offset..offset + announced_size],
@@ -138,7 +147,7 @@ impl Codec for InterfaceTxStatus {
                 offset += size.max(announced_size);
                 InterfaceTxStatus::D7asp(value)
             }
-            id => {
+            Err(id) => {
                 let WithSize {
                     value: size,
                     size: size_size,
@@ -19,7 +19,9 @@ pub mod interface_status;
 pub use interface_status::{InterfaceStatus, InterfaceStatusDecodingError};
 
 pub mod indirect_interface;
-pub use indirect_interface::{IndirectInterface, OverloadedIndirectInterface};
+pub use indirect_interface::{
+    IndirectInterface, NonOverloadedIndirectInterface, OverloadedIndirectInterface,
+};
 
 pub mod interface_tx_status;
 pub use interface_tx_status::{InterfaceTxStatus, InterfaceTxStatusDecodingError};